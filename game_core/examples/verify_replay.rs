@@ -0,0 +1,34 @@
+//! Replays a recording headless and verifies the checksum of the resulting world
+//! The registries are loaded from `assets` like the game does, such that the replay uses the same assets
+//! Usage: cargo run --example verify_replay -- <replay.bfsr> [assets dir]
+use game_core::{Recording, ReplayMismatch, World};
+
+fn main() {
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("usage: verify_replay <replay.bfsr> [assets dir]");
+        std::process::exit(2);
+    };
+    let assets = std::env::args().nth(2).unwrap_or_else(|| "assets".to_string());
+    let recording = match Recording::load(&path) {
+        Ok(recording) => recording,
+        Err(err) => {
+            eprintln!("failed to load {path}: {err}");
+            std::process::exit(2);
+        }
+    };
+    let mut world = World::new(recording.seed);
+    for (registry, err) in world.load_registries(&assets) {
+        eprintln!("failed to load {registry}, using builtin {registry}: {err}");
+    }
+    match recording.verify_with(world) {
+        Ok(()) => println!("{path}: ok ({} ticks)", recording.ticks.len()),
+        Err(ReplayMismatch::Registries { expected, actual }) => {
+            eprintln!("{path}: recorded with other assets, expected registries {expected:016x} got {actual:016x}");
+            std::process::exit(1);
+        }
+        Err(ReplayMismatch::World { expected, actual }) => {
+            eprintln!("{path}: checksum mismatch, expected {expected:016x} got {actual:016x}");
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use std::{collections::HashMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AssetError, asset};
+
+/// Behaviours compiled into the game, used when no behaviours are loaded from disk
+const BUILTIN_BEHAVIOURS: &str = include_str!("../../assets/behaviours.ron");
+
+/// State of a bot's state machine
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum AiState {
+    /// Stands still
+    #[default]
+    Idle,
+    /// Walks to random positions nearby
+    Wander,
+    /// Moves towards the target
+    Chase,
+    /// Attacks the target using its ability
+    Attack,
+    /// Moves away from the target
+    Flee,
+    /// Does nothing, the bot is dead
+    Dead,
+    /// Moves to the last known position of the target and looks around
+    Search,
+    /// Attacks a building standing in its way
+    Break,
+}
+
+/// Facts about a bot and its target, used to evaluate conditions
+#[derive(Clone, Default)]
+pub struct AiFacts {
+    pub is_dead: bool,
+    /// Whether the bot currently senses the target
+    pub sees_target: bool,
+    /// Whether the bot knows where the target is, or was last seen
+    pub knows_target: bool,
+    /// Distance to the target
+    pub target_distance: f32,
+    /// Whether the bot touches the target
+    pub target_in_contact: bool,
+    /// Whether the bot is within the light of the target
+    pub in_light: bool,
+    /// Health of the bot from 0..1
+    pub health_fraction: f32,
+    /// Time in seconds spent in the current state
+    pub state_time_sec: f32,
+    /// Whether the bot has arrived at the position it moves towards
+    pub arrived: bool,
+    /// Whether the bot touches a building standing between it and the position it moves towards
+    pub obstacle_in_contact: bool,
+}
+
+/// Condition of a transition between two states
+#[derive(Clone, Serialize, Deserialize)]
+pub enum Condition {
+    Always,
+    IsDead,
+    SeesTarget,
+    KnowsTarget,
+    /// Sees the target within the given distance
+    TargetWithin(f32),
+    TargetInContact,
+    InLight,
+    /// Health is below the given fraction from 0..1
+    HealthBelow(f32),
+    /// Spent more than the given seconds in the current state
+    StateTimeExceeds(f32),
+    Arrived,
+    ObstacleInContact,
+    Not(Box<Condition>),
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+}
+
+impl Condition {
+    pub fn evaluate(&self, facts: &AiFacts) -> bool {
+        match self {
+            Condition::Always => true,
+            Condition::IsDead => facts.is_dead,
+            Condition::SeesTarget => facts.sees_target,
+            Condition::KnowsTarget => facts.knows_target,
+            Condition::TargetWithin(distance) => facts.sees_target && facts.target_distance <= *distance,
+            Condition::TargetInContact => facts.target_in_contact,
+            Condition::InLight => facts.in_light,
+            Condition::HealthBelow(fraction) => facts.health_fraction < *fraction,
+            Condition::StateTimeExceeds(sec) => facts.state_time_sec > *sec,
+            Condition::Arrived => facts.arrived,
+            Condition::ObstacleInContact => facts.obstacle_in_contact,
+            Condition::Not(condition) => !condition.evaluate(facts),
+            Condition::All(conditions) => conditions.iter().all(|c| c.evaluate(facts)),
+            Condition::Any(conditions) => conditions.iter().any(|c| c.evaluate(facts)),
+        }
+    }
+}
+
+/// Transition to another state, taken when the condition holds
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Transition {
+    /// States the transition applies to, applies to every state when empty
+    #[serde(default)]
+    pub from: Vec<AiState>,
+    pub to: AiState,
+    pub condition: Condition,
+}
+
+/// A state machine describing the behaviour of a bot
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Behaviour {
+    /// Transitions in order of priority
+    pub transitions: Vec<Transition>,
+}
+
+impl Behaviour {
+    /// Returns the state to change to, if any
+    /// The first transition from the current state whose condition holds is taken
+    pub fn next_state(&self, state: AiState, facts: &AiFacts) -> Option<AiState> {
+        self.transitions
+            .iter()
+            .filter(|t| t.to != state && (t.from.is_empty() || t.from.contains(&state)))
+            .find(|t| t.condition.evaluate(facts))
+            .map(|t| t.to)
+    }
+}
+
+/// Registry of all bot behaviours by id
+#[derive(Clone)]
+pub struct BehaviourRegistry {
+    pub behaviours: HashMap<String, Behaviour>,
+}
+
+impl Default for BehaviourRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl BehaviourRegistry {
+    /// The behaviours compiled into the game
+    pub fn builtin() -> Self {
+        BehaviourRegistry {
+            behaviours: asset::parse_ron(BUILTIN_BEHAVIOURS).expect("builtin behaviours should be valid"),
+        }
+    }
+
+    /// Load behaviours from a RON file mapping behaviour ids to behaviours
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AssetError> {
+        Ok(BehaviourRegistry {
+            behaviours: asset::load_ron(path)?,
+        })
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Behaviour> {
+        self.behaviours.get(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zombie() -> Behaviour {
+        BehaviourRegistry::builtin().get("zombie").cloned().expect("zombie behaviour should exist")
+    }
+
+    #[test]
+    fn nothing_changes_without_a_matching_transition() {
+        let facts = AiFacts { health_fraction: 1.0, ..Default::default() };
+        assert_eq!(zombie().next_state(AiState::Idle, &facts), None);
+        assert_eq!(zombie().next_state(AiState::Chase, &AiFacts { sees_target: true, ..facts }), None);
+    }
+
+    #[test]
+    fn transitions_apply_only_from_their_states() {
+        let facts = AiFacts { state_time_sec: 5.0, ..Default::default() };
+        assert_eq!(zombie().next_state(AiState::Idle, &facts), Some(AiState::Wander));
+        assert_eq!(zombie().next_state(AiState::Wander, &facts), None);
+        assert_eq!(zombie().next_state(AiState::Dead, &AiFacts { sees_target: true, ..Default::default() }), None);
+    }
+
+    #[test]
+    fn transitions_without_states_apply_to_every_state_but_their_own() {
+        let facts = AiFacts { is_dead: true, ..Default::default() };
+        for state in [AiState::Idle, AiState::Wander, AiState::Chase, AiState::Attack, AiState::Search, AiState::Break] {
+            assert_eq!(zombie().next_state(state, &facts), Some(AiState::Dead));
+        }
+        assert_eq!(zombie().next_state(AiState::Dead, &facts), None);
+    }
+
+    #[test]
+    fn first_matching_transition_is_taken() {
+        // touching the target goes before seeing it
+        let facts = AiFacts { sees_target: true, knows_target: true, target_in_contact: true, ..Default::default() };
+        assert_eq!(zombie().next_state(AiState::Wander, &facts), Some(AiState::Attack));
+        let facts = AiFacts { target_in_contact: false, ..facts };
+        assert_eq!(zombie().next_state(AiState::Wander, &facts), Some(AiState::Chase));
+        let facts = AiFacts { sees_target: false, ..facts };
+        assert_eq!(zombie().next_state(AiState::Wander, &facts), Some(AiState::Search));
+    }
+
+    #[test]
+    fn conditions_combine() {
+        let facts = AiFacts { arrived: true, state_time_sec: 1.0, ..Default::default() };
+        assert_eq!(zombie().next_state(AiState::Wander, &facts), Some(AiState::Idle));
+        let facts = AiFacts { arrived: false, state_time_sec: 7.0, ..Default::default() };
+        assert_eq!(zombie().next_state(AiState::Wander, &facts), Some(AiState::Idle));
+
+        let condition = Condition::All(vec![Condition::SeesTarget, Condition::Not(Box::new(Condition::TargetWithin(2.0)))]);
+        assert!(condition.evaluate(&AiFacts { sees_target: true, target_distance: 3.0, ..Default::default() }));
+        assert!(!condition.evaluate(&AiFacts { sees_target: true, target_distance: 1.0, ..Default::default() }));
+        assert!(!condition.evaluate(&AiFacts { sees_target: false, target_distance: 3.0, ..Default::default() }));
+    }
+}
@@ -0,0 +1,31 @@
+use std::{fmt, io, path::Path};
+
+use serde::de::DeserializeOwned;
+
+#[derive(Debug)]
+pub enum AssetError {
+    Io(io::Error),
+    Format(String),
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetError::Io(err) => write!(f, "io error: {err}"),
+            AssetError::Format(err) => write!(f, "invalid asset: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+/// Parse a RON asset from a string
+pub fn parse_ron<T: DeserializeOwned>(data: &str) -> Result<T, AssetError> {
+    ron::from_str(data).map_err(|err| AssetError::Format(err.to_string()))
+}
+
+/// Load a RON asset from disk, such that it can be changed without recompiling
+pub fn load_ron<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T, AssetError> {
+    let data = std::fs::read_to_string(path).map_err(AssetError::Io)?;
+    parse_ron(&data)
+}
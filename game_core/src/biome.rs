@@ -0,0 +1,113 @@
+use glam::{IVec2, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::{Terrain, Texture, fractal_noise, value_noise};
+
+/// Region of the forest with its own ground, vegetation and amount of zombies
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Biome {
+    #[default]
+    DenseForest,
+    Clearing,
+    Swamp,
+    BurntForest,
+    Meadow,
+}
+
+/// Scale of the noise in tiles, i.e. roughly the size of the regions
+const VEGETATION_SCALE: f32 = 24.0;
+const MOISTURE_SCALE: f32 = 32.0;
+const FIRE_SCALE: f32 = 40.0;
+const CLEARING_SCALE: f32 = 8.0;
+const DETAIL_SCALE: f32 = 3.0;
+const TRAIL_SCALE: f32 = 48.0;
+const ELEVATION_SCALE: f32 = 64.0;
+
+/// Width of the trails, as the distance of the trail noise from its middle
+const TRAIL_WIDTH: f32 = 0.012;
+
+impl Biome {
+    /// Returns the biome at the tile, which only depends on the seed and the tile
+    pub fn at(seed: u64, tile_index: IVec2) -> Biome {
+        let p = tile_index.as_vec2();
+        let fire = value_noise(seed.wrapping_add(1), p / FIRE_SCALE);
+        let moisture = value_noise(seed.wrapping_add(2), p / MOISTURE_SCALE);
+        let vegetation = fractal_noise(seed.wrapping_add(3), p / VEGETATION_SCALE, 3);
+        let clearing = value_noise(seed.wrapping_add(4), p / CLEARING_SCALE);
+
+        if moisture > 0.7 {
+            Biome::Swamp
+        } else if fire > 0.75 {
+            Biome::BurntForest
+        } else if vegetation < 0.35 {
+            Biome::Meadow
+        } else if clearing > 0.8 {
+            Biome::Clearing
+        } else {
+            Biome::DenseForest
+        }
+    }
+
+    /// Returns the terrain of a tile within this biome
+    /// - trails wind through all biomes, following the contour lines of a noise
+    /// - high ground is covered in snow
+    /// - swamps have pools of water surrounded by mud, other biomes have scattered rocks
+    pub fn terrain_at(&self, seed: u64, tile_index: IVec2) -> Terrain {
+        let p = tile_index.as_vec2();
+        let trail = value_noise(seed.wrapping_add(6), p / TRAIL_SCALE);
+        if (trail - 0.5).abs() < TRAIL_WIDTH {
+            return Terrain::DirtPath;
+        }
+
+        let elevation = value_noise(seed.wrapping_add(7), p / ELEVATION_SCALE);
+        let detail = value_noise(seed.wrapping_add(5), p / DETAIL_SCALE + Vec2::splat(0.5));
+        match self {
+            Biome::Swamp if detail > 0.65 => Terrain::Water,
+            Biome::Swamp if detail > 0.52 => Terrain::Mud,
+            Biome::Swamp => Terrain::Grass,
+            _ if detail > 0.82 => Terrain::Rock,
+            _ if elevation > 0.78 => Terrain::Snow,
+            _ => Terrain::Grass,
+        }
+    }
+
+    /// Texture of the ground
+    pub fn texture(&self) -> Texture {
+        match self {
+            Biome::DenseForest | Biome::Clearing => Texture::Grass,
+            Biome::Swamp => Texture::SwampGrass,
+            Biome::BurntForest => Texture::Ash,
+            Biome::Meadow => Texture::MeadowGrass,
+        }
+    }
+
+    /// Chance of a tile to have a tree
+    pub fn tree_density(&self) -> f32 {
+        match self {
+            Biome::DenseForest => 0.2,
+            Biome::Clearing => 0.0,
+            Biome::Swamp => 0.04,
+            Biome::BurntForest => 0.1,
+            Biome::Meadow => 0.02,
+        }
+    }
+
+    /// Chance of a tile to have a zombie
+    pub fn zombie_density(&self) -> f32 {
+        match self {
+            Biome::DenseForest => 0.05,
+            Biome::Clearing => 0.01,
+            Biome::Swamp => 0.08,
+            Biome::BurntForest => 0.07,
+            Biome::Meadow => 0.02,
+        }
+    }
+
+    /// Prefab of the trees growing in this biome
+    pub fn tree_prefab(&self) -> &'static str {
+        match self {
+            Biome::BurntForest => "burnt_tree",
+            _ => "tree",
+        }
+    }
+}
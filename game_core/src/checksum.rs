@@ -0,0 +1,24 @@
+use std::hash::Hasher;
+
+/// FNV-1a hasher used to compute checksums of the world
+/// Unlike the std hashers, the result is stable across builds and platforms
+pub struct Checksum(u64);
+
+impl Default for Checksum {
+    fn default() -> Self {
+        Checksum(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for Checksum {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
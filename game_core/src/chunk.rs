@@ -0,0 +1,65 @@
+use std::hash::Hasher;
+
+use glam::IVec2;
+use serde::{Deserialize, Serialize};
+use slotmap::DefaultKey;
+
+use crate::{Checksum, Entity};
+
+/// Width and height of a chunk in tiles
+pub const CHUNK_SIZE: i32 = 16;
+
+/// Chunks within this distance of the player's chunk are loaded
+pub const CHUNK_LOAD_DISTANCE: i32 = 1;
+
+/// Chunks further away than this distance from the player's chunk are unloaded
+/// Larger than the load distance, such that chunks are not unloaded and loaded again
+/// when walking back and forth over a chunk border
+pub const CHUNK_UNLOAD_DISTANCE: i32 = 2;
+
+/// A square of tiles which is generated, loaded and unloaded as a whole
+/// Tiles are generated from the seed whenever the chunk is loaded,
+/// while entities are only spawned once and kept in the chunk while it is unloaded
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Chunk {
+    /// Whether the tiles and entities of this chunk are part of the world
+    pub loaded: bool,
+
+    /// Whether the entities of this chunk have been spawned
+    pub populated: bool,
+
+    /// Entities within this chunk while it is unloaded
+    pub entities: Vec<Entity>,
+
+    /// Ids the entities had before they were unloaded, in the same order as `entities`
+    /// Entities get new ids when loaded again, ids stored in other entities are changed to the new ones
+    pub entity_ids: Vec<DefaultKey>,
+}
+
+/// Returns the index of the chunk containing the tile
+pub fn chunk_index(tile_index: IVec2) -> IVec2 {
+    IVec2::new(tile_index.x.div_euclid(CHUNK_SIZE), tile_index.y.div_euclid(CHUNK_SIZE))
+}
+
+/// Returns the distance between two chunks, counting diagonal steps as one
+pub fn chunk_distance(a: IVec2, b: IVec2) -> i32 {
+    let d = (a - b).abs();
+    d.x.max(d.y)
+}
+
+/// Iterate the indices of all tiles within the chunk
+pub fn chunk_tiles(chunk_index: IVec2) -> impl Iterator<Item = IVec2> {
+    let origin = chunk_index * CHUNK_SIZE;
+    (0..CHUNK_SIZE).flat_map(move |y| (0..CHUNK_SIZE).map(move |x| origin + IVec2::new(x, y)))
+}
+
+/// Seed of the chunk's random number generator, derived from the world seed
+/// Generating a chunk does not depend on the order in which chunks are generated
+pub fn chunk_seed(seed: u64, chunk_index: IVec2) -> u64 {
+    let mut h = Checksum::default();
+    h.write_u64(seed);
+    h.write_i32(chunk_index.x);
+    h.write_i32(chunk_index.y);
+    h.finish()
+}
@@ -0,0 +1,118 @@
+use glam::Vec4;
+use serde::{Deserialize, Serialize};
+
+/// Length of a full day and night in seconds
+pub const DAY_LENGTH_SEC: f32 = 300.0;
+
+/// Number of nights the player has to survive to win
+pub const NIGHTS_TO_SURVIVE: u32 = 5;
+
+/// Time of day at which a new game starts, in the morning of the first day
+const START_TIME_OF_DAY: f32 = 0.15;
+
+/// Radius of the daylight around the player at noon and of the moonlight at midnight
+/// At night the player mostly has to rely on the torch
+const DAY_LIGHT_RADIUS: f32 = 8.0;
+const NIGHT_LIGHT_RADIUS: f32 = 1.5;
+
+/// Multiplier of how far zombies see and hear at midnight
+const NIGHT_AGGRESSION: f32 = 1.5;
+
+/// Zombies spawned per second around the player at midnight
+const NIGHT_SPAWN_RATE: f32 = 0.1;
+
+/// Part of the day
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Phase {
+    Dawn,
+    Day,
+    Dusk,
+    Night,
+}
+
+/// World clock driving the day and night cycle
+/// A day starts at dawn, such that the number of nights survived is the number of days passed
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Clock {
+    /// Time in seconds since the dawn of the first day
+    pub time_sec: f32,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Clock {
+            time_sec: START_TIME_OF_DAY * DAY_LENGTH_SEC,
+        }
+    }
+}
+
+impl Clock {
+    pub fn tick(&mut self, dt: f32) {
+        self.time_sec += dt;
+    }
+
+    /// Time of the current day from 0..1, starting at dawn
+    pub fn time_of_day(&self) -> f32 {
+        (self.time_sec / DAY_LENGTH_SEC).fract()
+    }
+
+    /// Number of the current day, starting at 1
+    pub fn day(&self) -> u32 {
+        self.nights_survived() + 1
+    }
+
+    pub fn nights_survived(&self) -> u32 {
+        (self.time_sec / DAY_LENGTH_SEC) as u32
+    }
+
+    /// Whether the player has survived enough nights to win
+    pub fn has_won(&self) -> bool {
+        self.nights_survived() >= NIGHTS_TO_SURVIVE
+    }
+
+    pub fn phase(&self) -> Phase {
+        match self.time_of_day() {
+            t if t < 0.1 => Phase::Dawn,
+            t if t < 0.5 => Phase::Day,
+            t if t < 0.6 => Phase::Dusk,
+            _ => Phase::Night,
+        }
+    }
+
+    /// Amount of daylight from 0..1, fading in at dawn and out at dusk
+    pub fn daylight(&self) -> f32 {
+        let t = self.time_of_day();
+        match self.phase() {
+            Phase::Dawn => t / 0.1,
+            Phase::Day => 1.0,
+            Phase::Dusk => 1.0 - (t - 0.5) / 0.1,
+            Phase::Night => 0.0,
+        }
+    }
+
+    /// Radius of the natural light around the player, without the torch
+    pub fn light_radius(&self) -> f32 {
+        NIGHT_LIGHT_RADIUS + (DAY_LIGHT_RADIUS - NIGHT_LIGHT_RADIUS) * self.daylight()
+    }
+
+    /// Color multiplied with everything drawn, bluish at night and warm at dawn and dusk
+    pub fn ambient_color(&self) -> Vec4 {
+        let night = Vec4::new(0.55, 0.6, 0.9, 1.0);
+        let day = match self.phase() {
+            Phase::Dawn | Phase::Dusk => Vec4::new(1.0, 0.8, 0.65, 1.0),
+            _ => Vec4::ONE,
+        };
+        night.lerp(day, self.daylight())
+    }
+
+    /// Multiplier of how far zombies see and hear
+    pub fn aggression(&self) -> f32 {
+        1.0 + (NIGHT_AGGRESSION - 1.0) * (1.0 - self.daylight())
+    }
+
+    /// Zombies spawned per second around the player, zombies only spawn in the dark
+    pub fn zombie_spawn_rate(&self) -> f32 {
+        NIGHT_SPAWN_RATE * (1.0 - self.daylight())
+    }
+}
@@ -0,0 +1,835 @@
+use glam::{IVec2, Vec2, Vec3, Vec4};
+use serde::{Deserialize, Serialize};
+use slotmap::DefaultKey;
+
+use crate::{AiState, Entity, Frame, ItemStack, Texture, Timer, WeaponDef};
+
+/// A component which can be attached to an entity and queried using [`crate::World::query`]
+pub trait Component: Sized + 'static {
+    fn get(entity: &Entity) -> Option<&Self>;
+    fn get_mut(entity: &mut Entity) -> Option<&mut Self>;
+}
+
+macro_rules! optional_component {
+    ($component:ty, $field:ident) => {
+        impl Component for $component {
+            fn get(entity: &Entity) -> Option<&Self> {
+                entity.$field.as_ref()
+            }
+
+            fn get_mut(entity: &mut Entity) -> Option<&mut Self> {
+                entity.$field.as_mut()
+            }
+        }
+    };
+}
+
+optional_component!(Body, body);
+optional_component!(Sprite, sprite);
+optional_component!(Health, health);
+optional_component!(Ability, ability);
+optional_component!(Mover, mover);
+optional_component!(Ai, ai);
+optional_component!(Perception, perception);
+optional_component!(Inventory, inventory);
+optional_component!(Item, item);
+optional_component!(Fellable, fellable);
+optional_component!(Crafting, crafting);
+optional_component!(LightSource, light_source);
+optional_component!(Survival, survival);
+optional_component!(Projectile, projectile);
+
+impl Component for Transform {
+    fn get(entity: &Entity) -> Option<&Self> {
+        Some(&entity.transform)
+    }
+
+    fn get_mut(entity: &mut Entity) -> Option<&mut Self> {
+        Some(&mut entity.transform)
+    }
+}
+
+/// Position and orientation of an entity
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Transform {
+    /// Position of this entity
+    pub pos: Vec3,
+
+    /// Facing direction in radians
+    pub facing: f32,
+}
+
+/// Physical body of an entity used for collision detection
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Body {
+    /// Whether this entity is solid (i.e., impassable)
+    pub solid: bool,
+
+    /// Interaction radius, for collision detection
+    pub radius: f32,
+}
+
+impl Default for Body {
+    fn default() -> Self {
+        Body {
+            solid: true,
+            radius: 0.4,
+        }
+    }
+}
+
+/// Visual representation of an entity
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sprite {
+    /// Texture used to represent this entity
+    pub texture: Texture,
+
+    /// Current animation frame of this entity
+    pub frame: Frame,
+
+    /// Size of the sprite representing this entity
+    pub size: Vec2,
+
+    /// Color tint applied to this entity, such as the charred look of burnt trees
+    pub tint: Vec4,
+
+    /// Timer for flash effects (e.g., when taking damage)
+    /// The flash is applied on top of the tint while the timer runs, see [`Sprite::color`]
+    pub flash_timer: Timer,
+
+    /// Optional text to display floating over the entity
+    pub floating_text: Option<String>,
+}
+
+impl Default for Sprite {
+    fn default() -> Self {
+        Sprite {
+            texture: Texture::None,
+            frame: Frame::Default,
+            size: Vec2::ONE,
+            tint: Vec4::ONE,
+            flash_timer: Timer::new(0.25, true),
+            floating_text: None,
+        }
+    }
+}
+
+impl Sprite {
+    /// Color of the red flash when taking damage
+    const FLASH_COLOR: Vec4 = Vec4::new(0.75, 0.0, 0.0, 1.0);
+
+    /// Color the sprite is drawn with, which is its tint with the damage flash multiplied over it while it runs
+    pub fn color(&self) -> Vec4 {
+        if self.flash_timer.finished() {
+            self.tint
+        } else {
+            self.tint * Self::FLASH_COLOR
+        }
+    }
+}
+
+/// Ability of an entity, such as swinging an axe or a zombie attack
+/// The timings follow the weapon the entity fights with, see [`crate::World::weapon`]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Ability {
+    /// Ability cooldown timer in seconds
+    pub timer_sec: f32,
+
+    /// Total duration of the ability cooldown in seconds
+    pub timer_total_sec: f32,
+
+    /// Time when the ability actives
+    pub activates_at_sec: f32,
+
+    /// Id of the weapon in the [`crate::WeaponRegistry`] used when no item is wielded, such as the player's axe
+    pub weapon: String,
+
+    /// Item whose weapon is used instead while it is carried, such as a spear
+    pub wielded_item: Option<String>,
+}
+
+impl Default for Ability {
+    fn default() -> Self {
+        Ability {
+            timer_sec: 0.0,
+            timer_total_sec: 1.0,
+            activates_at_sec: 0.5,
+            weapon: String::new(),
+            wielded_item: None,
+        }
+    }
+}
+
+impl Ability {
+    /// Take over the timings of the weapon
+    pub fn equip(&mut self, weapon: &WeaponDef) {
+        self.timer_total_sec = weapon.cooldown_sec;
+        self.activates_at_sec = (weapon.cooldown_sec - weapon.wind_up_sec).max(0.0);
+    }
+
+    /// Activate the ability, starting its cooldown timer
+    /// If the ability is already on cooldown, this has no effect
+    pub fn activate(&mut self) {
+        if self.timer_sec <= 0.0 {
+            self.timer_sec = self.timer_total_sec;
+        }
+    }
+
+    /// Resets the ability cooldown, making it immediately available
+    pub fn reset(&mut self) {
+        self.timer_sec = 0.0;
+    }
+
+    /// Returns a value between 0.0 and 1.0 representing the progress of the ability cooldown
+    pub fn delta(&self) -> f32 {
+        if self.timer_total_sec <= 0.0 {
+            0.0
+        } else {
+            1.0 - (self.timer_sec / self.timer_total_sec)
+        }
+    }
+
+    /// Returns true if the ability is currently in progress
+    pub fn is_in_progress(&self) -> bool {
+        self.timer_sec > 0.0
+    }
+}
+
+/// Movement of an entity
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Mover {
+    /// Movement direction of this entity
+    /// Normalized from 0..1
+    pub move_dir: Vec3,
+
+    /// Maximum movement speed of this entity
+    pub max_speed: f32,
+
+    /// sinus value for movement animation
+    /// used for e.g. bobbing up and down while walking
+    pub move_sinus: f32,
+
+    /// speed of the sinus movement
+    pub move_sinus_speed: f32,
+
+    /// total distance moved
+    /// reset when move_sinus is reset
+    pub move_distance_total: f32,
+
+    /// Whether the entity wants to sprint, sprinting uses up stamina
+    pub sprint: bool,
+
+    /// Multiplier of the speed while sprinting
+    pub sprint_multiplier: f32,
+
+    /// Whether the entity wants to dodge, cleared once the dodge started
+    pub dodge: bool,
+
+    /// Speed of a dodge
+    pub dodge_speed: f32,
+
+    /// Duration of a dodge in seconds, during which the entity cannot be damaged
+    pub dodge_sec: f32,
+
+    /// Direction of the dodge in progress
+    pub dodge_dir: Vec3,
+
+    /// Time in seconds until the dodge in progress ends
+    pub dodge_timer_sec: f32,
+}
+
+impl Default for Mover {
+    fn default() -> Self {
+        Mover {
+            move_dir: Vec3::ZERO,
+            max_speed: 0.0,
+            move_sinus: 0.0,
+            move_sinus_speed: 1.0,
+            move_distance_total: 0.0,
+            sprint: false,
+            sprint_multiplier: 1.6,
+            dodge: false,
+            dodge_speed: 8.0,
+            dodge_sec: 0.25,
+            dodge_dir: Vec3::ZERO,
+            dodge_timer_sec: 0.0,
+        }
+    }
+}
+
+impl Mover {
+    /// Returns true while a dodge is in progress
+    pub fn is_dodging(&self) -> bool {
+        self.dodge_timer_sec > 0.0
+    }
+}
+
+/// Allows an entity to be controlled by the bot system using a state machine
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Ai {
+    /// Id of the behaviour in the [`crate::BehaviourRegistry`]
+    pub behaviour: String,
+
+    /// Current state of the state machine
+    pub state: AiState,
+
+    /// Time in seconds spent in the current state
+    pub state_timer_sec: f32,
+
+    /// Position the bot walks towards while wandering
+    pub wander_target: Option<Vec3>,
+
+    /// Cached path of tiles towards the target, see [`crate::find_path`]
+    pub path: Vec<IVec2>,
+
+    /// Tile the cached path leads to
+    pub path_goal: Option<IVec2>,
+
+    /// Time in seconds until the path is planned again
+    pub replan_timer_sec: f32,
+}
+
+/// Senses used by a bot to detect the player
+/// The player is detected by sight within a cone, by standing within the player's light
+/// and by noises the player makes
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Perception {
+    /// Distance at which the player can be seen within the sight cone
+    pub sight_range: f32,
+
+    /// Half angle of the sight cone in radians
+    pub sight_angle: f32,
+
+    /// Multiplier of the radius at which noises are heard
+    pub hearing: f32,
+
+    /// Time in seconds the last known position of the player is remembered
+    pub memory_sec: f32,
+
+    /// Whether the player was seen this tick
+    pub sees_player: bool,
+
+    /// Last known position of the player, `None` if the player has been forgotten
+    pub last_known_pos: Option<Vec3>,
+
+    /// Time in seconds since the player was last seen or heard
+    pub time_since_detected_sec: f32,
+}
+
+impl Default for Perception {
+    fn default() -> Self {
+        Perception {
+            sight_range: 4.0,
+            sight_angle: 1.0,
+            hearing: 1.0,
+            memory_sec: 5.0,
+            sees_player: false,
+            last_known_pos: None,
+            time_since_detected_sec: 0.0,
+        }
+    }
+}
+
+impl Perception {
+    /// Remember the player at the given position
+    pub fn detect(&mut self, pos: Vec3) {
+        self.last_known_pos = Some(pos);
+        self.time_since_detected_sec = 0.0;
+    }
+}
+
+/// Slots of items carried by an entity
+/// Each slot holds a stack of a single kind of item
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Inventory {
+    pub slots: Vec<Option<ItemStack>>,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Inventory {
+            slots: vec![None; 8],
+        }
+    }
+}
+
+impl Inventory {
+    /// Add items to the inventory, filling stacks of the same item before empty slots
+    /// Returns the number of items which did not fit
+    pub fn add(&mut self, stack: &ItemStack, max_stack: u32) -> u32 {
+        let mut remaining = stack.count;
+        for slot in self.slots.iter_mut().flatten() {
+            if remaining == 0 {
+                break;
+            }
+            if slot.item == stack.item && slot.count < max_stack {
+                let n = remaining.min(max_stack - slot.count);
+                slot.count += n;
+                remaining -= n;
+            }
+        }
+        for slot in self.slots.iter_mut() {
+            if remaining == 0 {
+                break;
+            }
+            if slot.is_none() {
+                let n = remaining.min(max_stack);
+                *slot = Some(ItemStack::new(&stack.item, n));
+                remaining -= n;
+            }
+        }
+        remaining
+    }
+
+    /// Remove a number of items, taken from the last slots first
+    /// Nothing is removed and false is returned if there are not enough items
+    pub fn remove(&mut self, item: &str, count: u32) -> bool {
+        if self.count(item) < count {
+            return false;
+        }
+        let mut remaining = count;
+        for slot in self.slots.iter_mut().rev() {
+            let Some(stack) = slot else {
+                continue;
+            };
+            if stack.item != item {
+                continue;
+            }
+            let n = remaining.min(stack.count);
+            stack.count -= n;
+            remaining -= n;
+            if stack.count == 0 {
+                *slot = None;
+            }
+            if remaining == 0 {
+                break;
+            }
+        }
+        true
+    }
+
+    /// Remove a number of items from a single slot
+    /// Nothing is removed and false is returned if the slot holds fewer items
+    pub fn remove_from_slot(&mut self, slot: usize, count: u32) -> bool {
+        let Some(entry) = self.slots.get_mut(slot) else {
+            return false;
+        };
+        let Some(stack) = entry else {
+            return false;
+        };
+        if stack.count < count {
+            return false;
+        }
+        stack.count -= count;
+        if stack.count == 0 {
+            *entry = None;
+        }
+        true
+    }
+
+    /// Total number of the item across all slots
+    pub fn count(&self, item: &str) -> u32 {
+        self.slots
+            .iter()
+            .flatten()
+            .filter(|stack| stack.item == item)
+            .map(|stack| stack.count)
+            .sum()
+    }
+
+    /// Take the whole stack out of a slot
+    pub fn take(&mut self, slot: usize) -> Option<ItemStack> {
+        self.slots.get_mut(slot)?.take()
+    }
+}
+
+/// Crafting of a recipe in progress
+/// The inputs of the recipe have already been taken from the inventory
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Crafting {
+    /// Id of the recipe in the [`crate::RecipeRegistry`]
+    pub recipe: String,
+
+    /// Time in seconds until the output is received
+    pub timer_sec: f32,
+}
+
+/// Allows a dead entity, such as a chopped tree, to fall over and grow back after a while
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Fellable {
+    /// Time in seconds it takes to fall over, after which only the stump is left
+    pub fall_sec: f32,
+
+    /// Prefab growing back in place of the stump, the stump stays forever when `None`
+    pub regrow_prefab: Option<String>,
+
+    /// Time in seconds after being felled until the prefab grows back
+    pub regrow_sec: f32,
+
+    /// Time in seconds since the entity was felled
+    pub felled_sec: f32,
+}
+
+impl Default for Fellable {
+    fn default() -> Self {
+        Fellable {
+            fall_sec: 0.6,
+            regrow_prefab: None,
+            regrow_sec: 300.0,
+            felled_sec: 0.0,
+        }
+    }
+}
+
+/// Light emitted by an entity, such as the player's torch or a campfire
+/// Lights burning fuel dim as the fuel runs out
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LightSource {
+    /// Radius of the light with a full supply of fuel
+    pub radius: f32,
+
+    /// Color of the light
+    pub color: Vec3,
+
+    /// How much the radius flickers from 0..1, where 0 is a steady light
+    pub flicker: f32,
+
+    /// Time in seconds driving the flicker
+    pub flicker_sec: f32,
+
+    /// Whether the light burns fuel, lights without fuel shine forever
+    pub burns_fuel: bool,
+
+    /// Remaining fuel in seconds of burning
+    pub fuel_sec: f32,
+
+    /// Maximum fuel the light holds in seconds
+    pub max_fuel_sec: f32,
+}
+
+impl Default for LightSource {
+    fn default() -> Self {
+        LightSource {
+            radius: 5.0,
+            color: Vec3::new(1.0, 0.85, 0.65),
+            flicker: 0.05,
+            flicker_sec: 0.0,
+            burns_fuel: true,
+            fuel_sec: 180.0,
+            max_fuel_sec: 180.0,
+        }
+    }
+}
+
+impl LightSource {
+    /// Remaining fuel from 0..1
+    pub fn fuel_fraction(&self) -> f32 {
+        if !self.burns_fuel {
+            1.0
+        } else if self.max_fuel_sec <= 0.0 {
+            0.0
+        } else {
+            (self.fuel_sec / self.max_fuel_sec).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Radius of the light, shrinking as the fuel runs out
+    pub fn current_radius(&self) -> f32 {
+        // stays bright for most of the fuel and dies down quickly at the end
+        self.radius * self.fuel_fraction().sqrt()
+    }
+
+    /// Add fuel, returns false if the light is already full
+    pub fn refuel(&mut self, fuel_sec: f32) -> bool {
+        if self.fuel_sec >= self.max_fuel_sec {
+            return false;
+        }
+        self.fuel_sec = (self.fuel_sec + fuel_sec).min(self.max_fuel_sec);
+        true
+    }
+}
+
+/// Survival needs of an entity, which run out over time
+/// Each stat ranges from 0 to `max`, where `max` is best, e.g. a full stomach
+/// Running out of food, water or warmth drains health, running out of stamina exhausts the entity
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Survival {
+    /// Opposite of hunger, restored by eating
+    pub food: f32,
+
+    /// Opposite of thirst, restored by drinking next to water
+    pub water: f32,
+
+    /// Body temperature, drops at night and is restored by the sun and campfires
+    pub warmth: f32,
+
+    /// Used up by physical effort, such as sprinting, and recovers over time
+    pub stamina: f32,
+
+    /// Maximum of every stat
+    pub max: f32,
+
+    pub food_loss_per_sec: f32,
+    pub water_loss_per_sec: f32,
+
+    /// Warmth lost per second at midnight
+    pub warmth_loss_per_sec: f32,
+
+    pub stamina_regen_per_sec: f32,
+
+    /// Time in seconds after using stamina before it recovers
+    pub stamina_regen_delay_sec: f32,
+
+    /// Time in seconds since stamina was last used
+    pub exertion_timer_sec: f32,
+
+    /// Set when stamina runs out and cleared once it has recovered, sprinting is disabled while exhausted
+    pub exhausted: bool,
+
+    /// Time in seconds since health was last drained by depleted stats
+    pub drain_timer_sec: f32,
+}
+
+impl Default for Survival {
+    fn default() -> Self {
+        Survival {
+            food: 100.0,
+            water: 100.0,
+            warmth: 100.0,
+            stamina: 100.0,
+            max: 100.0,
+            food_loss_per_sec: 0.2,
+            water_loss_per_sec: 0.3,
+            warmth_loss_per_sec: 0.6,
+            stamina_regen_per_sec: 20.0,
+            stamina_regen_delay_sec: 1.0,
+            exertion_timer_sec: 0.0,
+            exhausted: false,
+            drain_timer_sec: 0.0,
+        }
+    }
+}
+
+impl Survival {
+    /// Number of food, water and warmth which have run out
+    pub fn depleted(&self) -> u32 {
+        [self.food, self.water, self.warmth].into_iter().filter(|v| *v <= 0.0).count() as u32
+    }
+
+    pub fn can_sprint(&self) -> bool {
+        !self.exhausted && self.stamina > 0.0
+    }
+
+    /// Use up stamina, returns false and uses nothing if there is not enough stamina
+    pub fn use_stamina(&mut self, amount: f32) -> bool {
+        if self.exhausted || self.stamina < amount {
+            return false;
+        }
+        self.stamina -= amount;
+        self.exertion_timer_sec = 0.0;
+        true
+    }
+
+    /// Restore stats by the given amounts, capped at the maximum
+    pub fn restore(&mut self, food: f32, water: f32, warmth: f32) {
+        self.food = (self.food + food).clamp(0.0, self.max);
+        self.water = (self.water + water).clamp(0.0, self.max);
+        self.warmth = (self.warmth + warmth).clamp(0.0, self.max);
+    }
+}
+
+/// Projectile flying through the world, such as a thrown stone or an arrow
+/// Flies until it hits an entity with a solid body or a solid tile, or until its lifetime runs out
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Projectile {
+    /// Speed at which the projectile is launched
+    pub speed: f32,
+
+    /// Current velocity of the projectile
+    pub velocity: Vec3,
+
+    /// Radius used when sweeping the projectile against bodies
+    pub radius: f32,
+
+    /// Damage dealt to the entity hit, taken from the weapon the projectile was launched with
+    pub damage: f32,
+
+    /// Time in seconds until the projectile drops to the ground
+    pub lifetime_sec: f32,
+
+    /// Entity which launched the projectile, it is never hit by its own projectile
+    pub owner: Option<DefaultKey>,
+
+    /// Item left on the ground where the projectile hits or lands, such as the arrow itself
+    pub drops: Option<String>,
+}
+
+impl Default for Projectile {
+    fn default() -> Self {
+        Projectile {
+            speed: 8.0,
+            velocity: Vec3::ZERO,
+            radius: 0.1,
+            damage: 0.0,
+            lifetime_sec: 1.5,
+            owner: None,
+            drops: None,
+        }
+    }
+}
+
+/// Stack of items lying on the ground, picked up by walking over it
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Item {
+    pub stack: ItemStack,
+
+    /// Time in seconds until the item can be picked up
+    /// Set when dropped, such that the item is not picked up again right away
+    #[serde(default)]
+    pub pickup_delay_sec: f32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+    pub can_receive_damage: bool,
+
+    /// Whether the entity is removed when it dies instead of leaving a corpse
+    pub despawn_on_death: bool,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Health {
+            current: 100.0,
+            max: 100.0,
+            can_receive_damage: true,
+            despawn_on_death: false,
+        }
+    }
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Health {
+            current: max,
+            max,
+            can_receive_damage: true,
+            despawn_on_death: false,
+        }
+    }
+
+    pub fn indistructible() -> Self {
+        Health {
+            current: 1.0,
+            max: 1.0,
+            can_receive_damage: false,
+            despawn_on_death: false,
+        }
+    }
+
+    pub fn apply_damage(&mut self, damage: f32) {
+        if self.can_receive_damage {
+            self.current -= damage;
+        }
+    }
+
+    pub fn heal(&mut self, amount: f32) {
+        self.current += amount;
+        if self.current > self.max {
+            self.current = self.max;
+        }
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.current > 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flash_is_multiplied_over_the_tint() {
+        let tint = Vec4::new(0.25, 0.2, 0.18, 1.0);
+        let mut sprite = Sprite {
+            tint,
+            ..Default::default()
+        };
+        assert_eq!(sprite.color(), tint);
+
+        sprite.flash_timer.restart();
+        assert_eq!(sprite.color(), tint * Vec4::new(0.75, 0.0, 0.0, 1.0));
+
+        // the tint is back once the flash is over
+        sprite.flash_timer.tick(0.3);
+        assert_eq!(sprite.color(), tint);
+        assert_eq!(sprite.tint, tint);
+    }
+
+    fn inventory(slots: usize) -> Inventory {
+        Inventory { slots: vec![None; slots] }
+    }
+
+    #[test]
+    fn add_fills_stacks_before_empty_slots() {
+        let mut inventory = inventory(3);
+        assert_eq!(inventory.add(&ItemStack::new("stone", 3), 5), 0);
+        assert_eq!(inventory.add(&ItemStack::new("wood", 1), 5), 0);
+        assert_eq!(inventory.add(&ItemStack::new("stone", 4), 5), 0);
+        // the first stack is topped up to 5, the rest overflows into the empty slot
+        assert_eq!(inventory.slots[0], Some(ItemStack::new("stone", 5)));
+        assert_eq!(inventory.slots[1], Some(ItemStack::new("wood", 1)));
+        assert_eq!(inventory.slots[2], Some(ItemStack::new("stone", 2)));
+        assert_eq!(inventory.count("stone"), 7);
+    }
+
+    #[test]
+    fn add_returns_what_does_not_fit() {
+        let mut inventory = inventory(2);
+        assert_eq!(inventory.add(&ItemStack::new("stone", 12), 5), 2);
+        assert_eq!(inventory.count("stone"), 10);
+        assert_eq!(inventory.add(&ItemStack::new("wood", 1), 5), 1);
+        assert_eq!(inventory.count("wood"), 0);
+    }
+
+    #[test]
+    fn remove_takes_from_the_last_slots_first() {
+        let mut inventory = inventory(3);
+        inventory.add(&ItemStack::new("stone", 8), 5);
+        assert!(inventory.remove("stone", 4));
+        assert_eq!(inventory.slots[0], Some(ItemStack::new("stone", 4)));
+        assert_eq!(inventory.slots[1], None);
+
+        // nothing is removed when there are not enough items
+        assert!(!inventory.remove("stone", 5));
+        assert_eq!(inventory.count("stone"), 4);
+        assert!(!inventory.remove("wood", 1));
+    }
+
+    #[test]
+    fn remove_from_slot_needs_enough_items_in_the_slot() {
+        let mut inventory = inventory(2);
+        inventory.add(&ItemStack::new("stone", 3), 5);
+        assert!(!inventory.remove_from_slot(0, 4));
+        assert!(!inventory.remove_from_slot(1, 1));
+        assert!(!inventory.remove_from_slot(5, 1));
+        assert!(inventory.remove_from_slot(0, 3));
+        assert_eq!(inventory.slots[0], None);
+    }
+}
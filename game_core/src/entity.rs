@@ -1,13 +1,18 @@
-use glam::{Vec2, Vec3, Vec4};
+use serde::{Deserialize, Serialize};
 
-use crate::{Frame, Texture, Timer};
+use crate::{Ability, Ai, Body, Component, Crafting, Fellable, Frame, Health, Inventory, Item, LightSource, Mover, Perception, Projectile, Sprite, Survival, Transform};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum EntityVariant {
     Unknown,
     Player,
     Tree,
     Zombie,
+    Crate,
+    Campfire,
+    Item,
+    /// Placed by the player, such as a wall or barricade
+    Building,
 }
 
 impl Default for EntityVariant {
@@ -16,153 +21,92 @@ impl Default for EntityVariant {
     }
 }
 
-#[derive(Default, Clone)]
+/// An entity is a set of components
+/// Every entity has a transform, all other components are optional
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub struct Entity {
-    /// Position of this entity
-    pub pos: glam::Vec3,
-
-    /// Variant of this entity, such as a player, monster, item, etc. 
+    /// Variant of this entity, such as a player, monster, item, etc.
     pub variant: EntityVariant,
 
-    /// Texture used to represent this entity
-    pub texture: Texture,
-
-    /// Current animation frame of this entity
-    pub frame: Frame,
-
-    /// Movement direction of this entity
-    /// Normalized from 0..1
-    pub move_dir: Vec3,
-
-    /// Facing direction in radians
-    pub facing: f32,
+    /// Position and orientation of this entity
+    pub transform: Transform,
 
-    /// Whether this entity is solid (i.e., impassable)
-    pub solid: bool,
+    /// Body used for collision detection
+    pub body: Option<Body>,
 
-    /// Interaction radius, for collision detection
-    pub radius:f32,
+    /// Sprite representing this entity
+    pub sprite: Option<Sprite>,
 
-    /// Size of the sprite representing this entity
-    pub sprite_size:Vec2,
+    /// Health of this entity, entities without health cannot be damaged
+    pub health: Option<Health>,
 
-    /// Ability cooldown timer in seconds
-    pub ability_timer_sec:f32,
+    /// Ability of this entity, such as an attack
+    pub ability: Option<Ability>,
 
-    /// Total duration of the ability cooldown in seconds
-    pub ability_timer_total_sec:f32,
+    /// Allows the entity to move
+    pub mover: Option<Mover>,
 
-    /// Time when the ability actives
-    pub ability_activates_at_sec:f32,
+    /// Allows the entity to be controlled by a bot
+    pub ai: Option<Ai>,
 
-    /// sinus value for movement animation
-    /// used for e.g. bobbing up and down while walking
-    pub move_sinus:f32,
+    /// Allows a bot to detect the player, bots without perception always know where the player is
+    pub perception: Option<Perception>,
 
-    /// speed of the sinus movement
-    pub move_sinus_speed:f32,
+    /// Items carried by this entity
+    pub inventory: Option<Inventory>,
 
-    /// total distance moved
-    /// reset when move_sinus is reset
-    pub move_distance_total:f32,
+    /// Makes this entity an item lying on the ground
+    pub item: Option<Item>,
 
-    /// Maximum movement speed of this entity
-    pub max_speed: f32,
+    /// Allows the entity to be felled and grow back
+    pub fellable: Option<Fellable>,
 
-    /// Optional text to display floating over the entity
-    pub floating_text: Option<String>,
+    /// Crafting in progress
+    pub crafting: Option<Crafting>,
 
-    /// Health of this entity
-    pub health: Health,
+    /// Light carried by this entity, such as a torch
+    pub light_source: Option<LightSource>,
 
-    /// Color tint applied to this entity
-    pub tint: Vec4,
+    /// Survival needs, such as hunger and thirst
+    pub survival: Option<Survival>,
 
-    /// Timer for flash effects (e.g., when taking damage)
-    pub flash_timer:Timer
+    /// Makes this entity fly through the world and hit what is in its way, such as an arrow
+    pub projectile: Option<Projectile>,
 }
 
 impl Entity {
     pub fn tile_index(&self) -> glam::IVec2 {
-        glam::IVec2::new(self.pos.x as i32, self.pos.y as i32)
+        self.transform.pos.truncate().floor().as_ivec2()
     }
 
-    /// Activate the entity's ability, starting its cooldown timer
-    /// If the ability is already on cooldown, this has no effect
-    pub fn active_ability(&mut self) {
-        if self.ability_timer_sec <= 0.0 {
-            self.ability_timer_sec = self.ability_timer_total_sec;
-        }
+    pub fn get<C: Component>(&self) -> Option<&C> {
+        C::get(self)
     }
 
-    /// Resets the ability cooldown, making it immediately available
-    pub fn reset_ability(&mut self) {
-        self.ability_timer_sec = 0.0;
+    pub fn get_mut<C: Component>(&mut self) -> Option<&mut C> {
+        C::get_mut(self)
     }
 
-    /// Returns a value between 0.0 and 1.0 representing the progress of the ability cooldown
-    pub fn ability_delta(&self) -> f32 {
-        if self.ability_timer_total_sec <= 0.0 {
-            0.0
-        } else {
-            1.0 - (self.ability_timer_sec / self.ability_timer_total_sec)
-        }
+    /// Returns true if the entity is alive
+    /// Entities without health are always considered alive
+    pub fn is_alive(&self) -> bool {
+        self.health.as_ref().is_none_or(|health| health.is_alive())
     }
 
-    /// Returns true if the ability is currently in progress
+    /// Returns true if the entity's ability is currently in progress
     pub fn is_ability_in_progress(&self) -> bool {
-        self.ability_timer_sec > 0.0
-    }
-}
-
-#[derive(Clone)]
-pub struct Health {
-    pub current: f32,
-    pub max: f32,
-    pub can_receive_damage: bool,
-}
-
-impl Default for Health {
-    fn default() -> Self {
-        Health {
-            current: 100.0,
-            max: 100.0,
-            can_receive_damage: true,
-        }
+        self.ability.as_ref().is_some_and(|ability| ability.is_in_progress())
     }
-}
 
-impl Health {
-    pub fn new(max: f32) -> Self {
-        Health {
-            current: max,
-            max,
-            can_receive_damage: true,
+    /// Sets the animation frame of the entity's sprite, if it has one
+    pub fn set_frame(&mut self, frame: Frame) {
+        if let Some(sprite) = &mut self.sprite {
+            sprite.frame = frame;
         }
     }
 
-    pub fn indistructible() -> Self {
-        Health {
-            current: 1.0,
-            max: 1.0,
-            can_receive_damage: false,
-        }
+    /// Returns the animation frame of the entity's sprite
+    pub fn frame(&self) -> Frame {
+        self.sprite.as_ref().map(|sprite| sprite.frame).unwrap_or_default()
     }
-
-    pub fn apply_damage(&mut self, damage: f32) {
-        if self.can_receive_damage {
-            self.current -= damage;
-        }
-    }
-
-    pub fn heal(&mut self, amount: f32) {
-        self.current += amount;
-        if self.current > self.max {
-            self.current = self.max;
-        }
-    }
-
-    pub fn is_alive(&self) -> bool {
-        self.current > 0.0
-    }
-}
\ No newline at end of file
+}
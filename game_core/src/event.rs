@@ -1,9 +1,10 @@
 use glam::Vec3;
+use serde::{Deserialize, Serialize};
 use slotmap::DefaultKey;
 
-use crate::entity::EntityVariant;
+use crate::PrefabOverrides;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum Event {
     Tick(TickEvent),
     PostTick(TickEvent),
@@ -15,16 +16,24 @@ pub enum Event {
     AbilityActived(AbilityActivedEvent),
     AbilityHit(AbilityHitEvent),
     DamageEntity(ApplyDamageEvent),
+    Noise(NoiseEvent),
+    Pickup(PickupEvent),
+    Drop(DropEvent),
+    Craft(CraftEvent),
+    Build(BuildEvent),
+    Refuel(RefuelEvent),
+    Consume(ConsumeEvent),
+    SwitchWeapon(SwitchWeaponEvent),
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ApplyDamageEvent {
     pub entity_id: DefaultKey,
     pub other_entity_id: DefaultKey,
     pub damage_amount: f32,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct PlayerInputEvent {
     pub player_id: DefaultKey,
     
@@ -37,42 +46,135 @@ pub struct PlayerInputEvent {
 
     /// whether the player used their ability this tick
     pub use_ability: bool,
+
+    /// inventory slot the player dropped this tick
+    pub drop_slot: Option<usize>,
+
+    /// recipe the player started crafting this tick
+    pub craft: Option<String>,
+
+    /// inventory slot the player built from this tick
+    pub build_slot: Option<usize>,
+
+    /// whether the player refuelled their torch this tick
+    pub refuel: bool,
+
+    /// inventory slot the player consumed an item from this tick
+    pub consume_slot: Option<usize>,
+
+    /// whether the player is holding the sprint key
+    pub sprint: bool,
+
+    /// whether the player dodged this tick
+    pub dodge: bool,
+
+    /// whether the player switched to their next weapon this tick
+    pub switch_weapon: bool,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct TickEvent {
     pub dt:f32,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CollisionEvent {
     pub entity_1_id: DefaultKey,
     pub entity_2_id: DefaultKey,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct RestartEvent {
 
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct SpawnEvent {
     pub pos:Vec3,
-    pub variant:EntityVariant,
+    /// Id of the prefab to spawn
+    pub prefab:String,
+    pub overrides:PrefabOverrides,
+}
+
+impl SpawnEvent {
+    pub fn new(pos:Vec3, prefab:&str) -> Self {
+        Self {
+            pos,
+            prefab: prefab.to_string(),
+            overrides: Default::default(),
+        }
+    }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct AbilityActivedEvent {
     pub entity_id: DefaultKey,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct AbilityHitEvent {
     pub entity_id: DefaultKey,
     pub target_entity_id: DefaultKey,
 }
 
-#[derive(Clone)]
+/// A noise made by an entity, such as footsteps or an axe swing
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NoiseEvent {
+    pub entity_id: DefaultKey,
+    pub pos: Vec3,
+    /// Distance at which the noise can be heard
+    pub radius: f32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct DespawnEvent {
     pub entity_id: DefaultKey,
-}
\ No newline at end of file
+}
+
+/// An entity picks up an item lying on the ground
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PickupEvent {
+    pub entity_id: DefaultKey,
+    pub item_entity_id: DefaultKey,
+}
+
+/// An entity drops the stack of an inventory slot onto the ground in front of it
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DropEvent {
+    pub entity_id: DefaultKey,
+    pub slot: usize,
+}
+
+/// An entity starts crafting a recipe from the items in its inventory
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CraftEvent {
+    pub entity_id: DefaultKey,
+    /// Id of the recipe in the [`crate::RecipeRegistry`]
+    pub recipe: String,
+}
+
+/// An entity places the item of an inventory slot onto the tile in front of it
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BuildEvent {
+    pub entity_id: DefaultKey,
+    pub slot: usize,
+}
+
+/// An entity burns an item of its inventory as fuel for its light
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RefuelEvent {
+    pub entity_id: DefaultKey,
+}
+
+/// An entity uses up an item of an inventory slot, such as eating berries
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ConsumeEvent {
+    pub entity_id: DefaultKey,
+    pub slot: usize,
+}
+
+/// An entity switches to the next weapon it carries, see [`crate::World::wieldable_items`]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SwitchWeaponEvent {
+    pub entity_id: DefaultKey,
+}
@@ -1,12 +1,16 @@
 use std::default;
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Frame {
     Default,
     Walk1,
     Walk2,
     ReadyAttack,
     Attack,
+    /// A felled tree falling over
+    Falling,
     Dead
 }
 
@@ -0,0 +1,109 @@
+use glam::{Vec2, Vec3, Vec4};
+
+use crate::{Event, Frame, PlayerInputEvent, RestartEvent, Texture, TickEvent, World, systems::{self, Ctx}};
+
+/// A draw call recorded by the [`HeadlessCtx`]
+#[derive(Clone, PartialEq)]
+pub enum DrawCall {
+    Tile {
+        origin: Vec3,
+        texture: Texture,
+        frame: Frame,
+        color: Vec4,
+    },
+    Sprite {
+        origin: Vec3,
+        texture: Texture,
+        frame: Frame,
+        color: Vec4,
+        scale: Vec2,
+    },
+    Flash {
+        color: Vec4,
+    },
+    Text {
+        origin: Vec3,
+        text: String,
+        color: Vec4,
+    },
+}
+
+/// Ctx implementation which runs the game without a window
+/// Every draw call is recorded into the frame log instead of being rendered,
+/// which makes it possible to run the systems in tests, bots and on servers
+#[derive(Default)]
+pub struct HeadlessCtx {
+    /// The simulated game world
+    pub world: World,
+
+    /// Draw calls recorded during the last step
+    pub frame_log: Vec<DrawCall>,
+}
+
+impl HeadlessCtx {
+    /// Creates a new headless context with a freshly restarted world using the given seed
+    pub fn new(seed: u64) -> Self {
+        Self::from_world(World::new(seed))
+    }
+
+    /// Creates a new headless context restarting the given world
+    /// The world keeps its seed and registries, such that a world with registries loaded from disk can be simulated
+    pub fn from_world(world: World) -> Self {
+        let mut ctx = Self {
+            world,
+            frame_log: Vec::new(),
+        };
+        ctx.world.events.push_back(Event::Restart(RestartEvent {}));
+        systems::process(&mut ctx);
+        ctx
+    }
+
+    /// Advances the simulation by `dt` seconds using the given player input
+    /// Mirrors a single frame of the windowed app: the input and tick are processed first,
+    /// followed by the post tick which renders into the frame log
+    pub fn step(&mut self, dt: f32, input: PlayerInputEvent) {
+        self.frame_log.clear();
+        self.world.events.push_back(Event::PlayerInput(input));
+        self.world.events.push_back(Event::Tick(TickEvent { dt }));
+        systems::process(self);
+        self.world.events.push_back(Event::PostTick(TickEvent { dt }));
+        systems::process(self);
+    }
+}
+
+impl Ctx for HeadlessCtx {
+    fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    fn draw_tile(&mut self, origin: Vec3, texture: Texture, frame: Frame, color: Vec4) {
+        self.frame_log.push(DrawCall::Tile {
+            origin,
+            texture,
+            frame,
+            color,
+        });
+    }
+
+    fn draw_sprite(&mut self, origin: Vec3, texture: Texture, frame: Frame, color: Vec4, scale: Vec2) {
+        self.frame_log.push(DrawCall::Sprite {
+            origin,
+            texture,
+            frame,
+            color,
+            scale,
+        });
+    }
+
+    fn draw_flash(&mut self, color: Vec4) {
+        self.frame_log.push(DrawCall::Flash { color });
+    }
+
+    fn draw_text(&mut self, origin: Vec3, text: String, color: Vec4) {
+        self.frame_log.push(DrawCall::Text {
+            origin,
+            text,
+            color,
+        });
+    }
+}
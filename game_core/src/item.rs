@@ -0,0 +1,122 @@
+use std::{collections::HashMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AssetError, Texture, asset};
+
+/// Items compiled into the game, used when no items are loaded from disk
+const BUILTIN_ITEMS: &str = include_str!("../../assets/items.ron");
+
+/// Definition of a kind of item, such as wood or berries
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ItemDef {
+    /// Name shown to the player
+    pub name: String,
+
+    /// Texture of the item when lying on the ground
+    pub texture: Texture,
+
+    /// Maximum number of items in a single inventory slot
+    pub max_stack: u32,
+
+    /// Prefab placed onto a tile when building with the item, such as a wall
+    pub places: Option<String>,
+
+    /// Seconds of fuel added to the light of the entity when burned, such as wood for the torch
+    pub fuel_sec: Option<f32>,
+
+    /// Effect of using up the item, such as eating berries
+    pub consumable: Option<Consumable>,
+
+    /// Weapon in the [`crate::WeaponRegistry`] used while wielding the item
+    pub weapon: Option<String>,
+}
+
+/// Amounts restored when consuming an item, see [`crate::Survival`]
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Consumable {
+    pub food: f32,
+    pub water: f32,
+    pub warmth: f32,
+    pub health: f32,
+}
+
+impl Default for ItemDef {
+    fn default() -> Self {
+        ItemDef {
+            name: String::new(),
+            texture: Texture::None,
+            max_stack: 1,
+            places: None,
+            fuel_sec: None,
+            consumable: None,
+            weapon: None,
+        }
+    }
+}
+
+/// A number of items of the same kind
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ItemStack {
+    /// Id of the item in the [`ItemRegistry`]
+    pub item: String,
+    pub count: u32,
+}
+
+impl ItemDef {
+    /// Text shown for a number of this item, such as "Wood x3"
+    pub fn label(&self, count: u32) -> String {
+        match count {
+            1 => self.name.clone(),
+            count => format!("{} x{}", self.name, count),
+        }
+    }
+}
+
+impl ItemStack {
+    pub fn new(item: &str, count: u32) -> Self {
+        ItemStack {
+            item: item.to_string(),
+            count,
+        }
+    }
+}
+
+/// Registry of all items by id
+#[derive(Clone)]
+pub struct ItemRegistry {
+    pub items: HashMap<String, ItemDef>,
+}
+
+impl Default for ItemRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl ItemRegistry {
+    /// The items compiled into the game
+    pub fn builtin() -> Self {
+        ItemRegistry {
+            items: asset::parse_ron(BUILTIN_ITEMS).expect("builtin items should be valid"),
+        }
+    }
+
+    /// Load items from a RON file mapping item ids to item definitions
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AssetError> {
+        Ok(ItemRegistry {
+            items: asset::load_ron(path)?,
+        })
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ItemDef> {
+        self.items.get(id)
+    }
+
+    /// Maximum stack size of the item, unknown items do not stack
+    pub fn max_stack(&self, id: &str) -> u32 {
+        self.get(id).map(|def| def.max_stack.max(1)).unwrap_or(1)
+    }
+}
@@ -6,8 +6,20 @@ mod event;
 pub use event::*;
 mod entity;
 pub use entity::*;
+mod component;
+pub use component::*;
 mod tile;
 pub use tile::*;
+mod terrain;
+pub use terrain::*;
+mod chunk;
+pub use chunk::*;
+mod noise;
+pub use noise::*;
+mod biome;
+pub use biome::*;
+mod structure;
+pub use structure::*;
 mod math;
 pub use math::*;
 mod frame;
@@ -15,4 +27,32 @@ pub use frame::*;
 mod texture;
 pub use texture::*;
 mod timer;
-pub use timer::*;
\ No newline at end of file
+pub use timer::*;
+mod clock;
+pub use clock::*;
+mod light;
+pub use light::*;
+mod rng;
+pub use rng::*;
+mod checksum;
+pub use checksum::*;
+mod replay;
+pub use replay::*;
+mod save;
+pub use save::*;
+mod asset;
+pub use asset::*;
+mod prefab;
+pub use prefab::*;
+mod item;
+pub use item::*;
+mod recipe;
+pub use recipe::*;
+mod weapon;
+pub use weapon::*;
+mod pathfinding;
+pub use pathfinding::*;
+mod ai;
+pub use ai::*;
+mod headless;
+pub use headless::*;
\ No newline at end of file
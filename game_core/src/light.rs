@@ -0,0 +1,54 @@
+use glam::Vec3;
+
+/// A light shining into the world, gathered from the light sources using [`crate::World::lights`]
+#[derive(Clone, Copy)]
+pub struct Light {
+    pub pos: Vec3,
+    pub color: Vec3,
+
+    /// Radius of the light including its flicker
+    pub radius: f32,
+}
+
+impl Light {
+    /// Color the light adds at the given position
+    pub fn color_at(&self, pos: Vec3) -> Vec3 {
+        self.color * falloff(pos.distance(self.pos), self.radius)
+    }
+}
+
+/// Brightness from 0..1 at distance `d` of a light reaching up to `max_distance`
+pub(crate) fn falloff(d:f32, max_distance:f32) -> f32 {
+    if max_distance <= 0.0 {
+        return 0.0;
+    }
+
+    // Inverse square falloff with smooth cutoff
+    // This provides more realistic light attenuation
+    let normalized_d = d / max_distance;
+
+    if normalized_d >= 1.0 {
+        return 0.0;
+    }
+
+    // Inverse square law: intensity = 1 / (1 + k*d^2)
+    // k controls the falloff rate
+    let k = 16.0;
+    let attenuation = 1.0 / (1.0 + k * normalized_d * normalized_d);
+
+    // Smooth cutoff near the edge to avoid hard boundaries
+    let edge_softness = 0.2;
+    if normalized_d > (1.0 - edge_softness) {
+        let edge_factor = (1.0 - normalized_d) / edge_softness;
+        attenuation * edge_factor
+    } else {
+        attenuation
+    }
+}
+
+/// Flicker of a light from 0..1 at the given time, where 0 is a steady light
+/// Sums a few sine waves such that the flicker looks irregular, `phase` keeps lights from flickering in sync
+pub(crate) fn flicker(time_sec: f32, phase: f32) -> f32 {
+    let wave = (time_sec * 7.3 + phase).sin() * 0.5 + (time_sec * 13.1 + phase * 1.7).sin() * 0.3 + (time_sec * 23.9 + phase * 2.3).sin() * 0.2;
+    wave * 0.5 + 0.5
+}
@@ -1,4 +1,4 @@
-use glam::Vec2;
+use glam::{IVec2, Vec2};
 
 /// Returns the intersection point of two line segments, if they intersect
 pub fn line_intersect(
@@ -35,4 +35,187 @@ pub fn line_intersect(
     } else {
         None
     }
-}
\ No newline at end of file
+}
+
+/// Returns the correction needed to push a circle out of an axis aligned box, if they overlap
+/// The correction is along the collision normal, which lets a moving circle slide along the box
+pub fn circle_aabb_penetration(
+    center: Vec2,
+    radius: f32,
+    min: Vec2,
+    max: Vec2,
+) -> Option<Vec2> {
+    let closest = center.clamp(min, max);
+    let d = center - closest;
+    let dist = d.length();
+    if dist >= radius {
+        return None;
+    }
+
+    if dist > 0.0 {
+        return Some(d / dist * (radius - dist));
+    }
+
+    // Center is inside the box, push out through the closest side
+    let left = center.x - min.x;
+    let right = max.x - center.x;
+    let bottom = center.y - min.y;
+    let top = max.y - center.y;
+    let smallest = left.min(right).min(bottom).min(top);
+    if smallest == left {
+        Some(Vec2::new(-(left + radius), 0.0))
+    } else if smallest == right {
+        Some(Vec2::new(right + radius, 0.0))
+    } else if smallest == bottom {
+        Some(Vec2::new(0.0, -(bottom + radius)))
+    } else {
+        Some(Vec2::new(0.0, top + radius))
+    }
+}
+
+/// Returns the distance from a point to the closest point on a line segment
+pub fn distance_to_segment(p: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let len_sq = ab.length_squared();
+    if len_sq == 0.0 {
+        return p.distance(a);
+    }
+    let t = ((p - a).dot(ab) / len_sq).clamp(0.0, 1.0);
+    p.distance(a + ab * t)
+}
+
+/// Returns the fraction along a line segment at which it first touches a circle, if it does
+/// A segment starting inside the circle touches it right away
+pub fn segment_circle_intersect(a: Vec2, b: Vec2, center: Vec2, radius: f32) -> Option<f32> {
+    let d = b - a;
+    let f = a - center;
+    let c = f.length_squared() - radius * radius;
+    if c <= 0.0 {
+        return Some(0.0);
+    }
+    let len_sq = d.length_squared();
+    if len_sq == 0.0 {
+        return None;
+    }
+
+    // solve |a + d * t - center| = radius for the first t
+    let half_b = f.dot(d);
+    let discriminant = half_b * half_b - len_sq * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let t = (-half_b - discriminant.sqrt()) / len_sq;
+    (0.0..=1.0).contains(&t).then_some(t)
+}
+
+/// Walks the grid cells a line segment passes through in order, until `is_hit` returns true for a cell
+/// Returns the fraction along the segment at which the hit cell is entered
+pub fn first_cell_hit(from: Vec2, to: Vec2, mut is_hit: impl FnMut(IVec2) -> bool) -> Option<f32> {
+    let mut cell = from.floor().as_ivec2();
+    if is_hit(cell) {
+        return Some(0.0);
+    }
+    let end = to.floor().as_ivec2();
+    let d = to - from;
+    let step = IVec2::new(if d.x > 0.0 { 1 } else { -1 }, if d.y > 0.0 { 1 } else { -1 });
+
+    // fraction of the segment needed to cross a whole cell, and to reach the next cell boundary
+    let delta = Vec2::new(1.0 / d.x.abs(), 1.0 / d.y.abs());
+    let boundary = |from: f32, cell: i32, d: f32, delta: f32| {
+        if d == 0.0 {
+            f32::INFINITY
+        } else if d > 0.0 {
+            (cell as f32 + 1.0 - from) * delta
+        } else {
+            (from - cell as f32) * delta
+        }
+    };
+    let mut t_max = Vec2::new(boundary(from.x, cell.x, d.x, delta.x), boundary(from.y, cell.y, d.y, delta.y));
+
+    let cells = (end.x - cell.x).abs() + (end.y - cell.y).abs();
+    for _ in 0..cells {
+        let t = t_max.x.min(t_max.y);
+        if t_max.x < t_max.y {
+            cell.x += step.x;
+            t_max.x += delta.x;
+        } else {
+            cell.y += step.y;
+            t_max.y += delta.y;
+        }
+        if is_hit(cell) {
+            return Some(t.min(1.0));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(t: Option<f32>, expected: f32) -> bool {
+        t.is_some_and(|t| (t - expected).abs() < 1e-5)
+    }
+
+    #[test]
+    fn segment_touches_the_near_side_of_the_circle() {
+        let (a, b) = (Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0));
+        assert!(approx(segment_circle_intersect(a, b, Vec2::new(5.0, 0.0), 1.0), 0.4));
+        assert!(approx(segment_circle_intersect(b, a, Vec2::new(5.0, 0.0), 1.0), 0.4));
+        assert!(approx(segment_circle_intersect(a, b, Vec2::new(5.0, 1.0), 1.0), 0.5));
+    }
+
+    #[test]
+    fn segment_starting_inside_the_circle_touches_it_right_away() {
+        assert_eq!(segment_circle_intersect(Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(0.5, 0.0), 1.0), Some(0.0));
+        assert_eq!(segment_circle_intersect(Vec2::ZERO, Vec2::ZERO, Vec2::ZERO, 1.0), Some(0.0));
+    }
+
+    #[test]
+    fn segment_misses_circles_beside_behind_and_beyond_it() {
+        let (a, b) = (Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0));
+        assert_eq!(segment_circle_intersect(a, b, Vec2::new(5.0, 1.5), 1.0), None);
+        assert_eq!(segment_circle_intersect(a, b, Vec2::new(-3.0, 0.0), 1.0), None);
+        assert_eq!(segment_circle_intersect(a, b, Vec2::new(12.0, 0.0), 1.0), None);
+        assert_eq!(segment_circle_intersect(a, a, Vec2::new(5.0, 0.0), 1.0), None);
+    }
+
+    /// Cells visited by the segment, when nothing is hit
+    fn cells(from: Vec2, to: Vec2) -> Vec<IVec2> {
+        let mut cells = Vec::new();
+        let hit = first_cell_hit(from, to, |cell| {
+            cells.push(cell);
+            false
+        });
+        assert_eq!(hit, None);
+        cells
+    }
+
+    #[test]
+    fn cells_are_visited_in_order_without_gaps() {
+        for (from, to) in [
+            (Vec2::new(0.5, 0.5), Vec2::new(5.5, 0.5)),
+            (Vec2::new(0.2, 0.7), Vec2::new(4.9, 3.1)),
+            (Vec2::new(3.5, -1.2), Vec2::new(-2.5, 2.5)),
+            (Vec2::new(-0.5, 4.5), Vec2::new(-0.5, -3.5)),
+        ] {
+            let cells = cells(from, to);
+            assert_eq!(cells.first(), Some(&from.floor().as_ivec2()));
+            assert_eq!(cells.last(), Some(&to.floor().as_ivec2()));
+            for step in cells.windows(2) {
+                assert_eq!((step[1] - step[0]).abs().element_sum(), 1);
+            }
+        }
+        assert_eq!(cells(Vec2::new(0.5, 0.5), Vec2::new(0.7, 0.2)), vec![IVec2::ZERO]);
+    }
+
+    #[test]
+    fn hit_is_at_the_boundary_of_the_cell() {
+        let (from, to) = (Vec2::new(0.5, 0.5), Vec2::new(5.5, 0.5));
+        assert!(approx(first_cell_hit(from, to, |cell| cell.x == 3), 0.5));
+        assert!(approx(first_cell_hit(to, from, |cell| cell.x == 3), 0.3));
+        assert!(approx(first_cell_hit(Vec2::new(0.5, 0.5), Vec2::new(2.5, 2.5), |cell| cell.y == 1), 0.25));
+        assert_eq!(first_cell_hit(from, to, |cell| cell.x == 0), Some(0.0));
+        assert_eq!(first_cell_hit(from, to, |cell| cell.x == 6), None);
+    }
+}
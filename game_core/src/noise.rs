@@ -0,0 +1,52 @@
+use std::hash::Hasher;
+
+use glam::Vec2;
+
+use crate::Checksum;
+
+/// Returns a random value from 0..1 for the lattice point
+fn lattice(seed: u64, x: i32, y: i32) -> f32 {
+    let mut h = Checksum::default();
+    h.write_u64(seed);
+    h.write_i32(x);
+    h.write_i32(y);
+
+    // mix the bits, such that neighbouring lattice points are unrelated
+    let mut z = h.finish();
+    z ^= z >> 33;
+    z = z.wrapping_mul(0xff51afd7ed558ccd);
+    z ^= z >> 33;
+    (z >> 40) as f32 / (1u64 << 24) as f32
+}
+
+/// Smooth value noise from 0..1, with features roughly one unit apart
+/// The same seed and position always give the same value
+pub fn value_noise(seed: u64, p: Vec2) -> f32 {
+    let cell = p.floor();
+    let (x, y) = (cell.x as i32, cell.y as i32);
+    let f = p - cell;
+    let u = f * f * (Vec2::splat(3.0) - 2.0 * f);
+
+    let a = lattice(seed, x, y);
+    let b = lattice(seed, x + 1, y);
+    let c = lattice(seed, x, y + 1);
+    let d = lattice(seed, x + 1, y + 1);
+    let top = a + (b - a) * u.x;
+    let bottom = c + (d - c) * u.x;
+    top + (bottom - top) * u.y
+}
+
+/// Value noise from 0..1 summed over several octaves, adding finer detail with each octave
+pub fn fractal_noise(seed: u64, p: Vec2, octaves: u32) -> f32 {
+    let mut sum = 0.0;
+    let mut total = 0.0;
+    let mut amplitude = 1.0;
+    let mut frequency = 1.0;
+    for octave in 0..octaves {
+        sum += value_noise(seed.wrapping_add(octave as u64), p * frequency) * amplitude;
+        total += amplitude;
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+    sum / total
+}
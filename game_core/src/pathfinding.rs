@@ -0,0 +1,218 @@
+use std::{cmp::Reverse, collections::{BinaryHeap, HashMap}};
+
+use glam::IVec2;
+
+use crate::World;
+
+/// Maximum number of tiles expanded while searching for a path
+pub const MAX_SEARCH_NODES: usize = 2048;
+
+const STRAIGHT_COST: i32 = 10;
+const DIAGONAL_COST: i32 = 14;
+
+/// Extra cost of walking through a tile occupied by another moving entity
+/// Makes bots spread out instead of queueing behind each other
+const OCCUPIED_COST: i32 = 10;
+
+const NEIGHBOURS: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(-1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(0, -1),
+    IVec2::new(1, 1),
+    IVec2::new(-1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, -1),
+];
+
+/// Returns true if the tile cannot be walked through
+/// Solid tiles, tiles which have not been generated yet and tiles
+/// occupied by solid entities which cannot move (e.g. trees) are blocked
+pub fn is_tile_blocked(world: &World, tile_index: IVec2) -> bool {
+    let Some(tile) = world.tiles.get(tile_index) else {
+        return true;
+    };
+    if tile.is_solid() {
+        return true;
+    }
+    tile.entities.keys().any(|entity_id| {
+        world.entity(*entity_id).is_some_and(|e| {
+            e.mover.is_none() && e.body.as_ref().is_some_and(|body| body.solid)
+        })
+    })
+}
+
+/// Returns true if the tile is occupied by a solid entity which can move, such as another zombie
+fn is_tile_occupied(world: &World, tile_index: IVec2) -> bool {
+    let Some(tile) = world.tiles.get(tile_index) else {
+        return false;
+    };
+    tile.entities.keys().any(|entity_id| {
+        world.entity(*entity_id).is_some_and(|e| {
+            e.mover.is_some() && e.body.as_ref().is_some_and(|body| body.solid)
+        })
+    })
+}
+
+/// Octile distance between two tiles
+fn heuristic(a: IVec2, b: IVec2) -> i32 {
+    let d = (a - b).abs();
+    let (min, max) = (d.x.min(d.y), d.x.max(d.y));
+    DIAGONAL_COST * min + STRAIGHT_COST * (max - min)
+}
+
+/// Find a path of tiles from `start` to `goal` using A*
+/// The returned path excludes the start tile and ends with the goal tile
+/// Diagonal moves are not allowed to cut corners of blocked tiles
+/// Returns `None` if no path was found within `max_nodes` expanded tiles
+///
+/// `endlessgrid::Grid::astar` is not used since it only moves in four directions at a uniform cost
+/// and has no search limit, such that an unreachable goal would expand every loaded tile,
+/// while bots walk diagonally, avoid mud and other bots, and search many times per tick
+pub fn find_path(world: &World, start: IVec2, goal: IVec2, max_nodes: usize) -> Option<Vec<IVec2>> {
+    if start == goal {
+        return Some(Vec::new());
+    }
+    if is_tile_blocked(world, goal) {
+        return None;
+    }
+
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<IVec2, IVec2> = HashMap::new();
+    let mut cost: HashMap<IVec2, i32> = HashMap::new();
+    cost.insert(start, 0);
+    open.push(Reverse((heuristic(start, goal), 0, start.x, start.y)));
+
+    let mut expanded = 0;
+    while let Some(Reverse((_, g, x, y))) = open.pop() {
+        let current = IVec2::new(x, y);
+        if current == goal {
+            let mut path = vec![current];
+            let mut current = current;
+            while let Some(previous) = came_from.get(&current) {
+                if *previous == start {
+                    break;
+                }
+                path.push(*previous);
+                current = *previous;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        // skip outdated entries of tiles which have since been reached more cheaply
+        if cost.get(&current).is_some_and(|c| *c < g) {
+            continue;
+        }
+
+        expanded += 1;
+        if expanded > max_nodes {
+            return None;
+        }
+
+        for offset in NEIGHBOURS {
+            let next = current + offset;
+            if is_tile_blocked(world, next) {
+                continue;
+            }
+            let diagonal = offset.x != 0 && offset.y != 0;
+            if diagonal
+                && (is_tile_blocked(world, current + IVec2::new(offset.x, 0))
+                    || is_tile_blocked(world, current + IVec2::new(0, offset.y)))
+            {
+                continue;
+            }
+
+            let mut step_cost = if diagonal { DIAGONAL_COST } else { STRAIGHT_COST };
+            // slow terrain such as mud is avoided, faster terrain is not preferred
+            // such that the heuristic never overestimates the cost
+            let speed_multiplier = world.terrain(next).speed_multiplier();
+            if speed_multiplier < 1.0 {
+                step_cost = (step_cost as f32 / speed_multiplier) as i32;
+            }
+            if next != goal && is_tile_occupied(world, next) {
+                step_cost += OCCUPIED_COST;
+            }
+            let next_cost = g + step_cost;
+            if cost.get(&next).is_some_and(|c| *c <= next_cost) {
+                continue;
+            }
+            cost.insert(next, next_cost);
+            came_from.insert(next, current);
+            open.push(Reverse((next_cost + heuristic(next, goal), next_cost, next.x, next.y)));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::IVec2;
+
+    use super::*;
+    use crate::{Terrain, Tile};
+
+    /// World with open grass from (0, 0) to (size - 1, size - 1) and the given rock tiles
+    fn world(size: i32, rocks: &[(i32, i32)]) -> World {
+        let mut world = World::default();
+        for y in 0..size {
+            for x in 0..size {
+                let terrain = if rocks.contains(&(x, y)) { Terrain::Rock } else { Terrain::Grass };
+                world.tiles.insert(IVec2::new(x, y), Tile { terrain, ..Default::default() });
+            }
+        }
+        world
+    }
+
+    #[test]
+    fn walks_diagonally_over_open_ground() {
+        let world = world(8, &[]);
+        let path = find_path(&world, IVec2::new(0, 0), IVec2::new(5, 5), MAX_SEARCH_NODES).unwrap();
+        assert_eq!(path.len(), 5);
+        assert_eq!(path.last(), Some(&IVec2::new(5, 5)));
+    }
+
+    #[test]
+    fn does_not_cut_blocked_corners() {
+        let world = world(4, &[(1, 0)]);
+        let path = find_path(&world, IVec2::new(0, 0), IVec2::new(1, 1), MAX_SEARCH_NODES).unwrap();
+        assert_eq!(path, vec![IVec2::new(0, 1), IVec2::new(1, 1)]);
+    }
+
+    #[test]
+    fn walks_around_walls() {
+        let world = world(6, &[(2, 0), (2, 1), (2, 2), (2, 3)]);
+        let path = find_path(&world, IVec2::new(0, 0), IVec2::new(4, 0), MAX_SEARCH_NODES).unwrap();
+        assert_eq!(path.last(), Some(&IVec2::new(4, 0)));
+        assert!(path.iter().all(|tile| !is_tile_blocked(&world, *tile)));
+        assert!(path.contains(&IVec2::new(2, 4)));
+    }
+
+    #[test]
+    fn unreachable_goal_has_no_path() {
+        let world = world(6, &[(3, 2), (4, 2), (5, 2), (3, 3), (5, 3), (3, 4), (4, 4), (5, 4)]);
+        assert_eq!(find_path(&world, IVec2::new(0, 0), IVec2::new(4, 3), MAX_SEARCH_NODES), None);
+    }
+
+    #[test]
+    fn blocked_or_ungenerated_goal_has_no_path() {
+        let world = world(4, &[(3, 3)]);
+        assert_eq!(find_path(&world, IVec2::new(0, 0), IVec2::new(3, 3), MAX_SEARCH_NODES), None);
+        assert_eq!(find_path(&world, IVec2::new(0, 0), IVec2::new(10, 0), MAX_SEARCH_NODES), None);
+    }
+
+    #[test]
+    fn gives_up_after_max_nodes() {
+        let world = world(32, &[]);
+        let (start, goal) = (IVec2::new(0, 0), IVec2::new(31, 0));
+        assert_eq!(find_path(&world, start, goal, 8), None);
+        assert!(find_path(&world, start, goal, MAX_SEARCH_NODES).is_some());
+    }
+
+    #[test]
+    fn start_is_goal() {
+        let world = world(2, &[]);
+        assert_eq!(find_path(&world, IVec2::new(1, 1), IVec2::new(1, 1), MAX_SEARCH_NODES), Some(Vec::new()));
+    }
+}
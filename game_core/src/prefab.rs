@@ -0,0 +1,79 @@
+use std::{collections::HashMap, path::Path};
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::{Ability, Ai, AssetError, Body, EntityVariant, Fellable, Health, Inventory, Item, LightSource, Mover, Perception, Projectile, Sprite, Survival, asset};
+
+/// Prefabs compiled into the game, used when no prefabs are loaded from disk
+const BUILTIN_PREFABS: &str = include_str!("../../assets/prefabs.ron");
+
+/// Archetype of an entity, describing the components it is spawned with
+/// Components which are left out are not attached to the entity
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Prefab {
+    pub variant: EntityVariant,
+    pub body: Option<Body>,
+    pub sprite: Option<Sprite>,
+
+    /// When set, the sprite size is picked randomly between the size of the sprite and this
+    pub sprite_size_max: Option<Vec2>,
+
+    pub health: Option<Health>,
+    pub ability: Option<Ability>,
+    pub mover: Option<Mover>,
+    pub ai: Option<Ai>,
+    pub perception: Option<Perception>,
+    pub inventory: Option<Inventory>,
+    pub item: Option<Item>,
+    pub fellable: Option<Fellable>,
+    pub light_source: Option<LightSource>,
+    pub survival: Option<Survival>,
+    pub projectile: Option<Projectile>,
+}
+
+/// Optional per spawn overrides of prefab values
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct PrefabOverrides {
+    pub sprite_size: Option<Vec2>,
+    pub max_speed: Option<f32>,
+    pub health: Option<f32>,
+    pub floating_text: Option<String>,
+    /// Items of an item entity, also sets its texture and floating text from the item registry
+    pub item: Option<Item>,
+    /// Projectile of a launched projectile, with the velocity, damage and owner it was launched with
+    pub projectile: Option<Projectile>,
+}
+
+/// Registry of all prefabs by id
+#[derive(Clone)]
+pub struct PrefabRegistry {
+    pub prefabs: HashMap<String, Prefab>,
+}
+
+impl Default for PrefabRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl PrefabRegistry {
+    /// The prefabs compiled into the game
+    pub fn builtin() -> Self {
+        PrefabRegistry {
+            prefabs: asset::parse_ron(BUILTIN_PREFABS).expect("builtin prefabs should be valid"),
+        }
+    }
+
+    /// Load prefabs from a RON file mapping prefab ids to prefabs
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AssetError> {
+        Ok(PrefabRegistry {
+            prefabs: asset::load_ron(path)?,
+        })
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Prefab> {
+        self.prefabs.get(id)
+    }
+}
@@ -0,0 +1,66 @@
+use std::{collections::BTreeMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AssetError, EntityVariant, Inventory, ItemStack, asset};
+
+/// Recipes compiled into the game, used when no recipes are loaded from disk
+const BUILTIN_RECIPES: &str = include_str!("../../assets/recipes.ron");
+
+/// Describes how items are crafted from other items
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Recipe {
+    /// Items consumed when crafting starts
+    pub inputs: Vec<ItemStack>,
+
+    /// Items added to the inventory when crafting finishes
+    pub output: ItemStack,
+
+    /// Time in seconds it takes to craft
+    #[serde(default)]
+    pub craft_sec: f32,
+
+    /// Kind of entity which must be nearby while crafting, such as a campfire
+    #[serde(default)]
+    pub station: Option<EntityVariant>,
+}
+
+impl Recipe {
+    /// Whether the inventory holds all inputs of the recipe
+    pub fn has_inputs(&self, inventory: &Inventory) -> bool {
+        self.inputs.iter().all(|input| inventory.count(&input.item) >= input.count)
+    }
+}
+
+/// Registry of all recipes by id
+/// Ordered, such that the recipes are listed in the same order every time
+#[derive(Clone)]
+pub struct RecipeRegistry {
+    pub recipes: BTreeMap<String, Recipe>,
+}
+
+impl Default for RecipeRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl RecipeRegistry {
+    /// The recipes compiled into the game
+    pub fn builtin() -> Self {
+        RecipeRegistry {
+            recipes: asset::parse_ron(BUILTIN_RECIPES).expect("builtin recipes should be valid"),
+        }
+    }
+
+    /// Load recipes from a RON file mapping recipe ids to recipes
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AssetError> {
+        Ok(RecipeRegistry {
+            recipes: asset::load_ron(path)?,
+        })
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Recipe> {
+        self.recipes.get(id)
+    }
+}
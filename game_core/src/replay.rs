@@ -0,0 +1,331 @@
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use glam::Vec3;
+
+use crate::{HeadlessCtx, PlayerInputEvent, World};
+
+/// Input of the player for a single recorded tick
+#[derive(Clone, PartialEq)]
+pub struct RecordedTick {
+    pub dt: f32,
+    pub move_dir: Vec3,
+    pub facing: f32,
+    pub use_ability: bool,
+    pub drop_slot: Option<usize>,
+    pub craft: Option<String>,
+    pub build_slot: Option<usize>,
+    pub refuel: bool,
+    pub consume_slot: Option<usize>,
+    pub sprint: bool,
+    pub dodge: bool,
+    pub switch_weapon: bool,
+}
+
+/// A recorded stream of player input
+/// Replaying the ticks on a world created from the same seed reproduces the recorded run,
+/// which is verified using the checksum of the world at the end of the recording
+#[derive(Clone, Default)]
+pub struct Recording {
+    /// Seed of the recorded world
+    pub seed: u64,
+    /// Recorded ticks in order
+    pub ticks: Vec<RecordedTick>,
+    /// Checksum of the world after the last recorded tick
+    pub checksum: u64,
+    /// Checksum of the registries the run was recorded with, `None` for recordings older than version 9
+    pub registries: Option<u64>,
+}
+
+/// Returned when a replay does not reproduce the recorded world
+#[derive(Debug)]
+pub enum ReplayMismatch {
+    /// The replay uses other registries than the recording, such as prefabs loaded from other assets
+    Registries { expected: u64, actual: u64 },
+    /// The replayed world differs from the recorded world
+    World { expected: u64, actual: u64 },
+}
+
+impl Recording {
+    /// Magic bytes identifying a recording file
+    const MAGIC: &'static [u8; 4] = b"BFSR";
+    /// Version of the recording file format
+    /// Version 2 added dropping items, version 3 crafting, version 4 building, version 5 refuelling,
+    /// version 6 consuming items, version 7 sprinting and dodging, version 8 switching weapons
+    /// and version 9 the checksum of the registries
+    /// Older recordings can still be read, but the simulation has changed since they were made,
+    /// so replaying them is not expected to reproduce their checksum
+    const VERSION: u16 = 9;
+
+    /// Ticks allocated up front when reading, an hour at 60 ticks per second
+    const MAX_PREALLOCATED_TICKS: usize = 60 * 60 * 60;
+
+    const FLAG_USE_ABILITY: u8 = 1;
+    /// Followed by the dropped slot as a single byte
+    const FLAG_DROP: u8 = 2;
+    /// Followed by the length of the recipe id as a single byte and the id itself
+    const FLAG_CRAFT: u8 = 4;
+    /// Followed by the slot built from as a single byte
+    const FLAG_BUILD: u8 = 8;
+    const FLAG_REFUEL: u8 = 16;
+    /// Followed by the consumed slot as a single byte
+    const FLAG_CONSUME: u8 = 32;
+    const FLAG_SPRINT: u8 = 64;
+    const FLAG_DODGE: u8 = 128;
+
+    /// Second byte of flags, written since version 8
+    const FLAG_SWITCH_WEAPON: u8 = 1;
+
+    pub fn new(seed: u64) -> Self {
+        Recording {
+            seed,
+            ticks: Vec::new(),
+            checksum: 0,
+            registries: None,
+        }
+    }
+
+    /// Record the input and delta time of a single tick
+    pub fn record(&mut self, dt: f32, input: &PlayerInputEvent) {
+        self.ticks.push(RecordedTick {
+            dt,
+            move_dir: input.move_dir,
+            facing: input.facing,
+            use_ability: input.use_ability,
+            drop_slot: input.drop_slot,
+            craft: input.craft.clone(),
+            build_slot: input.build_slot,
+            refuel: input.refuel,
+            consume_slot: input.consume_slot,
+            sprint: input.sprint,
+            dodge: input.dodge,
+            switch_weapon: input.switch_weapon,
+        });
+    }
+
+    /// Store the checksum of the world and its registries, marking the end of the recording
+    /// Recording can continue afterwards, in which case `finish` should be called again
+    pub fn finish(&mut self, world: &World) {
+        self.checksum = world.checksum();
+        self.registries = Some(world.registries_checksum());
+    }
+
+    /// Replay the recording on a new headless world using the builtin registries
+    /// Returns the resulting context, such that the world can be inspected
+    pub fn replay(&self) -> HeadlessCtx {
+        self.replay_with(World::new(self.seed))
+    }
+
+    /// Replay the recording on the given world, which is restarted first
+    /// Used to replay runs recorded with registries loaded from disk, the seed of the world is replaced by the recorded seed
+    pub fn replay_with(&self, mut world: World) -> HeadlessCtx {
+        world.seed = self.seed;
+        let mut ctx = HeadlessCtx::from_world(world);
+        for tick in &self.ticks {
+            let input = PlayerInputEvent {
+                player_id: ctx.world.player,
+                move_dir: tick.move_dir,
+                facing: tick.facing,
+                use_ability: tick.use_ability,
+                drop_slot: tick.drop_slot,
+                craft: tick.craft.clone(),
+                build_slot: tick.build_slot,
+                refuel: tick.refuel,
+                consume_slot: tick.consume_slot,
+                sprint: tick.sprint,
+                dodge: tick.dodge,
+                switch_weapon: tick.switch_weapon,
+            };
+            ctx.step(tick.dt, input);
+        }
+        ctx
+    }
+
+    /// Replay the recording using the builtin registries and verify that the resulting world matches the recorded checksum
+    pub fn verify(&self) -> Result<(), ReplayMismatch> {
+        self.verify_with(World::new(self.seed))
+    }
+
+    /// Replay the recording on the given world and verify that the resulting world matches the recorded checksum
+    /// Fails without replaying when the registries of the world differ from the recorded ones
+    pub fn verify_with(&self, world: World) -> Result<(), ReplayMismatch> {
+        if let Some(expected) = self.registries {
+            let actual = world.registries_checksum();
+            if actual != expected {
+                return Err(ReplayMismatch::Registries { expected, actual });
+            }
+        }
+        let actual = self.replay_with(world).world.checksum();
+        if actual == self.checksum {
+            Ok(())
+        } else {
+            Err(ReplayMismatch::World {
+                expected: self.checksum,
+                actual,
+            })
+        }
+    }
+
+    /// Write the recording using the compact binary format
+    /// All values are stored little endian
+    pub fn write(&self, w: &mut impl Write) -> io::Result<()> {
+        w.write_all(Self::MAGIC)?;
+        w.write_all(&Self::VERSION.to_le_bytes())?;
+        w.write_all(&self.seed.to_le_bytes())?;
+        w.write_all(&self.checksum.to_le_bytes())?;
+        // zero marks a recording which was never finished, which has no registries to check
+        w.write_all(&self.registries.unwrap_or_default().to_le_bytes())?;
+        w.write_all(&(self.ticks.len() as u32).to_le_bytes())?;
+        for tick in &self.ticks {
+            for v in [tick.dt, tick.move_dir.x, tick.move_dir.y, tick.move_dir.z, tick.facing] {
+                w.write_all(&v.to_le_bytes())?;
+            }
+            let mut flags = 0;
+            if tick.use_ability {
+                flags |= Self::FLAG_USE_ABILITY;
+            }
+            if tick.drop_slot.is_some() {
+                flags |= Self::FLAG_DROP;
+            }
+            if tick.craft.is_some() {
+                flags |= Self::FLAG_CRAFT;
+            }
+            if tick.build_slot.is_some() {
+                flags |= Self::FLAG_BUILD;
+            }
+            if tick.refuel {
+                flags |= Self::FLAG_REFUEL;
+            }
+            if tick.consume_slot.is_some() {
+                flags |= Self::FLAG_CONSUME;
+            }
+            if tick.sprint {
+                flags |= Self::FLAG_SPRINT;
+            }
+            if tick.dodge {
+                flags |= Self::FLAG_DODGE;
+            }
+            let mut more_flags = 0;
+            if tick.switch_weapon {
+                more_flags |= Self::FLAG_SWITCH_WEAPON;
+            }
+            w.write_all(&[flags, more_flags])?;
+            if let Some(slot) = tick.drop_slot {
+                let slot = u8::try_from(slot).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "drop slot out of range"))?;
+                w.write_all(&[slot])?;
+            }
+            if let Some(recipe) = &tick.craft {
+                let len = u8::try_from(recipe.len()).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "recipe id too long"))?;
+                w.write_all(&[len])?;
+                w.write_all(recipe.as_bytes())?;
+            }
+            if let Some(slot) = tick.build_slot {
+                let slot = u8::try_from(slot).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "build slot out of range"))?;
+                w.write_all(&[slot])?;
+            }
+            if let Some(slot) = tick.consume_slot {
+                let slot = u8::try_from(slot).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "consume slot out of range"))?;
+                w.write_all(&[slot])?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Read a recording written by [`Recording::write`]
+    pub fn read(r: &mut impl Read) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        r.read_exact(&mut magic)?;
+        if &magic != Self::MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a recording"));
+        }
+        let version = u16::from_le_bytes(read_bytes(r)?);
+        if version == 0 || version > Self::VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported recording version {version}"),
+            ));
+        }
+        let seed = u64::from_le_bytes(read_bytes(r)?);
+        let checksum = u64::from_le_bytes(read_bytes(r)?);
+        let registries = if version >= 9 {
+            Some(u64::from_le_bytes(read_bytes(r)?)).filter(|registries| *registries != 0)
+        } else {
+            None
+        };
+        let len = u32::from_le_bytes(read_bytes(r)?);
+        // the length is not trusted for the allocation, a corrupt file ends with an error once the ticks run out
+        let mut ticks = Vec::with_capacity((len as usize).min(Self::MAX_PREALLOCATED_TICKS));
+        for _ in 0..len {
+            let mut v = [0.0; 5];
+            for v in v.iter_mut() {
+                *v = f32::from_le_bytes(read_bytes(r)?);
+            }
+            let [flags] = read_bytes(r)?;
+            let [more_flags] = if version >= 8 { read_bytes(r)? } else { [0] };
+            let drop_slot = if flags & Self::FLAG_DROP != 0 {
+                let [slot] = read_bytes(r)?;
+                Some(slot as usize)
+            } else {
+                None
+            };
+            let craft = if flags & Self::FLAG_CRAFT != 0 {
+                let [len] = read_bytes(r)?;
+                let mut recipe = vec![0; len as usize];
+                r.read_exact(&mut recipe)?;
+                let recipe = String::from_utf8(recipe).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+                Some(recipe)
+            } else {
+                None
+            };
+            let build_slot = if flags & Self::FLAG_BUILD != 0 {
+                let [slot] = read_bytes(r)?;
+                Some(slot as usize)
+            } else {
+                None
+            };
+            let consume_slot = if flags & Self::FLAG_CONSUME != 0 {
+                let [slot] = read_bytes(r)?;
+                Some(slot as usize)
+            } else {
+                None
+            };
+            ticks.push(RecordedTick {
+                dt: v[0],
+                move_dir: Vec3::new(v[1], v[2], v[3]),
+                facing: v[4],
+                use_ability: flags & Self::FLAG_USE_ABILITY != 0,
+                drop_slot,
+                craft,
+                build_slot,
+                refuel: flags & Self::FLAG_REFUEL != 0,
+                consume_slot,
+                sprint: flags & Self::FLAG_SPRINT != 0,
+                dodge: flags & Self::FLAG_DODGE != 0,
+                switch_weapon: more_flags & Self::FLAG_SWITCH_WEAPON != 0,
+            });
+        }
+        Ok(Recording {
+            seed,
+            ticks,
+            checksum,
+            registries,
+        })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut w = io::BufWriter::new(std::fs::File::create(path)?);
+        self.write(&mut w)?;
+        w.flush()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut r = io::BufReader::new(std::fs::File::open(path)?);
+        Self::read(&mut r)
+    }
+}
+
+fn read_bytes<const N: usize>(r: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    r.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
@@ -0,0 +1,132 @@
+use std::{fmt, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::World;
+
+/// Version of the save format written by [`World::save`]
+/// Bump this when the layout of the world changes and add a migration to [`migrate`]
+pub const SAVE_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    Format(String),
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(err) => write!(f, "io error: {err}"),
+            SaveError::Format(err) => write!(f, "invalid save: {err}"),
+            SaveError::UnsupportedVersion(version) => write!(f, "unsupported save version {version}"),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<io::Error> for SaveError {
+    fn from(err: io::Error) -> Self {
+        SaveError::Io(err)
+    }
+}
+
+impl From<ron::Error> for SaveError {
+    fn from(err: ron::Error) -> Self {
+        SaveError::Format(err.to_string())
+    }
+}
+
+impl From<ron::error::SpannedError> for SaveError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        SaveError::Format(err.to_string())
+    }
+}
+
+#[derive(Serialize)]
+struct SaveFileRef<'a> {
+    version: u32,
+    world: &'a World,
+}
+
+#[derive(Deserialize)]
+struct SaveFile {
+    world: World,
+}
+
+/// Only the version is read first, such that older layouts can be migrated
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+impl World {
+    /// Serialize the complete world, including pending events, into a versioned save
+    pub fn to_save_string(&self) -> Result<String, SaveError> {
+        let save = SaveFileRef {
+            version: SAVE_VERSION,
+            world: self,
+        };
+        Ok(ron::to_string(&save)?)
+    }
+
+    /// Deserialize a world from a save, migrating it from older versions if needed
+    pub fn from_save_str(data: &str) -> Result<World, SaveError> {
+        let header: SaveHeader = ron::from_str(data)?;
+        migrate(header.version, data)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SaveError> {
+        std::fs::write(path, self.to_save_string()?)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<World, SaveError> {
+        let data = std::fs::read_to_string(path)?;
+        World::from_save_str(&data)
+    }
+}
+
+/// Migration hook which turns a save of the given version into a world
+/// When the save format changes, the old layout should be kept around as a separate type,
+/// deserialized here and converted into the current world
+fn migrate(version: u32, data: &str) -> Result<World, SaveError> {
+    match version {
+        SAVE_VERSION => {
+            let save: SaveFile = ron::from_str(data)?;
+            Ok(save.world)
+        }
+        _ => Err(SaveError::UnsupportedVersion(version)),
+    }
+}
+
+/// Serializes the endless tile grid as a list of tile indices and tiles
+pub(crate) mod tiles {
+    use glam::IVec2;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::Tile;
+
+    pub fn serialize<S: Serializer>(tiles: &endlessgrid::Grid<Tile>, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut list: Vec<(IVec2, &Tile)> = Vec::new();
+        for chunk in tiles {
+            for (index, tile) in chunk {
+                list.push((index.into(), tile));
+            }
+        }
+        // sort to keep saves of identical worlds identical
+        list.sort_by_key(|(index, _)| (index.y, index.x));
+        list.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<endlessgrid::Grid<Tile>, D::Error> {
+        let list: Vec<(IVec2, Tile)> = Vec::deserialize(deserializer)?;
+        let mut tiles = endlessgrid::Grid::default();
+        for (index, tile) in list {
+            tiles.insert(index, tile);
+        }
+        Ok(tiles)
+    }
+}
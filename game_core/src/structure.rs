@@ -0,0 +1,309 @@
+use std::{collections::{BTreeMap, HashMap}, f32::consts::PI, path::Path};
+
+use glam::{IVec2, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::{AssetError, CHUNK_SIZE, Rng, Terrain, asset, chunk_distance, chunk_seed, value_noise};
+
+/// Structures compiled into the game, used when no structures are loaded from disk
+const BUILTIN_STRUCTURES: &str = include_str!("../../assets/structures.ron");
+
+/// Chance of a chunk to contain a structure
+const STRUCTURE_CHANCE: f32 = 0.3;
+
+/// Mixed into the seed, such that placing structures is unrelated to other generation
+const STRUCTURE_SALT: u64 = 0x5354_5255_4354;
+
+/// Maximum distance in tiles a path winds away from the straight line between two structures
+const PATH_WIGGLE: f32 = 2.5;
+
+/// Tiles within this distance of the origin are never covered by a structure
+const START_CLEARING: i32 = 2;
+
+/// A part of a structure, changing the terrain and/or spawning a prefab
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StructureCell {
+    /// Terrain of the tile, the generated terrain is kept when left out
+    pub terrain: Option<Terrain>,
+
+    /// Prefab spawned on the tile
+    pub prefab: Option<String>,
+
+    /// Whether paths from other structures lead to this tile, such as a door
+    pub entrance: bool,
+}
+
+/// A multi-tile prefab stamped into the world during generation, such as a cabin
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Structure {
+    /// Chance of this structure relative to the other structures
+    pub weight: f32,
+
+    /// Rows of the layout, each character refers to a cell of the legend
+    /// Spaces and unknown characters leave the generated tile untouched
+    pub layout: Vec<String>,
+
+    /// Ordered, such that the registry checksum of the world does not depend on the hash map order
+    pub legend: BTreeMap<char, StructureCell>,
+}
+
+impl Structure {
+    /// Width and height of the layout in tiles
+    pub fn size(&self) -> IVec2 {
+        let width = self.layout.iter().map(|row| row.chars().count()).max().unwrap_or_default();
+        IVec2::new(width as i32, self.layout.len() as i32)
+    }
+
+    /// Iterate the cells of the layout and their offsets from the top left corner
+    pub fn cells(&self) -> impl Iterator<Item = (IVec2, &StructureCell)> {
+        self.layout.iter().enumerate().flat_map(move |(y, row)| {
+            row.chars()
+                .enumerate()
+                .filter_map(move |(x, c)| Some((IVec2::new(x as i32, y as i32), self.legend.get(&c)?)))
+        })
+    }
+}
+
+/// Registry of all structures by id
+/// Ordered, such that choosing a structure from the seed is deterministic
+#[derive(Clone)]
+pub struct StructureRegistry {
+    pub structures: BTreeMap<String, Structure>,
+}
+
+impl Default for StructureRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl StructureRegistry {
+    /// The structures compiled into the game
+    pub fn builtin() -> Self {
+        StructureRegistry {
+            structures: asset::parse_ron(BUILTIN_STRUCTURES).expect("builtin structures should be valid"),
+        }
+    }
+
+    /// Load structures from a RON file mapping structure ids to structures
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AssetError> {
+        Ok(StructureRegistry {
+            structures: asset::load_ron(path)?,
+        })
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Structure> {
+        self.structures.get(id)
+    }
+
+    /// Returns the structure placed within the chunk, if any
+    /// Only depends on the seed and the chunk, such that chunks can be generated in any order
+    pub fn placed_in_chunk(&self, seed: u64, chunk_index: IVec2) -> Option<PlacedStructure<'_>> {
+        let mut rng = Rng::new(chunk_seed(seed ^ STRUCTURE_SALT, chunk_index));
+        if rng.next_f32() >= STRUCTURE_CHANCE {
+            return None;
+        }
+
+        let total_weight: f32 = self.structures.values().map(|s| s.weight).sum();
+        let mut pick = rng.next_f32() * total_weight;
+        let structure = self.structures.values().find(|s| {
+            pick -= s.weight;
+            pick < 0.0
+        })?;
+
+        // structures are placed fully within the chunk
+        let size = structure.size();
+        if size.x > CHUNK_SIZE || size.y > CHUNK_SIZE {
+            return None;
+        }
+        let x = (rng.next_u32() % (CHUNK_SIZE - size.x + 1) as u32) as i32;
+        let y = (rng.next_u32() % (CHUNK_SIZE - size.y + 1) as u32) as i32;
+        let origin = chunk_index * CHUNK_SIZE + IVec2::new(x, y);
+
+        // keep the start of the player clear
+        let min = origin - IVec2::splat(START_CLEARING);
+        let max = origin + size + IVec2::splat(START_CLEARING);
+        if min.x <= 0 && min.y <= 0 && max.x > 0 && max.y > 0 {
+            return None;
+        }
+
+        let entrance = structure
+            .cells()
+            .find(|(_, cell)| cell.entrance)
+            .map(|(offset, _)| offset)
+            .unwrap_or(size / 2);
+        Some(PlacedStructure {
+            structure,
+            origin,
+            entrance: origin + entrance,
+        })
+    }
+
+    /// Returns the cells of all structures and paths covering tiles within the chunk, by tile index
+    /// Each structure is connected by a path to the closest structure in the neighbouring chunks
+    pub fn cells_in_chunk(&self, seed: u64, chunk_index: IVec2) -> HashMap<IVec2, StructureCell> {
+        let mut cells = HashMap::new();
+        let in_chunk = |tile_index: IVec2| crate::chunk_index(tile_index) == chunk_index;
+
+        // paths can reach into the chunk from structures up to two chunks away
+        for y in -2..=2 {
+            for x in -2..=2 {
+                let from_chunk = chunk_index + IVec2::new(x, y);
+                let Some(from) = self.placed_in_chunk(seed, from_chunk) else {
+                    continue;
+                };
+                let Some(to) = self.closest_neighbour(seed, from_chunk, from.entrance) else {
+                    continue;
+                };
+                for tile_index in winding_path(seed, from.entrance, to) {
+                    if in_chunk(tile_index) {
+                        cells.insert(tile_index, StructureCell {
+                            terrain: Some(Terrain::DirtPath),
+                            ..Default::default()
+                        });
+                    }
+                }
+            }
+        }
+
+        if let Some(placed) = self.placed_in_chunk(seed, chunk_index) {
+            for (offset, cell) in placed.structure.cells() {
+                cells.insert(placed.origin + offset, cell.clone());
+            }
+        }
+        cells
+    }
+
+    /// Returns the entrance of the closest structure in the chunks around the given chunk
+    fn closest_neighbour(&self, seed: u64, chunk_index: IVec2, entrance: IVec2) -> Option<IVec2> {
+        let mut closest: Option<IVec2> = None;
+        for y in -1..=1 {
+            for x in -1..=1 {
+                let other_chunk = chunk_index + IVec2::new(x, y);
+                if chunk_distance(other_chunk, chunk_index) == 0 {
+                    continue;
+                }
+                let Some(other) = self.placed_in_chunk(seed, other_chunk) else {
+                    continue;
+                };
+                let distance = (other.entrance - entrance).length_squared();
+                if closest.is_none_or(|closest| distance < (closest - entrance).length_squared()) {
+                    closest = Some(other.entrance);
+                }
+            }
+        }
+        closest
+    }
+}
+
+/// A structure placed in the world
+pub struct PlacedStructure<'a> {
+    pub structure: &'a Structure,
+
+    /// Tile of the top left corner of the layout
+    pub origin: IVec2,
+
+    /// Tile paths lead to
+    pub entrance: IVec2,
+}
+
+/// Returns the tiles of a path winding from one tile to another
+/// The path is the same no matter which end it is generated from
+fn winding_path(seed: u64, a: IVec2, b: IVec2) -> Vec<IVec2> {
+    // order the ends, such that both directions give the same path
+    let (a, b) = if (a.x, a.y) <= (b.x, b.y) { (a, b) } else { (b, a) };
+    let start = a.as_vec2() + Vec2::splat(0.5);
+    let end = b.as_vec2() + Vec2::splat(0.5);
+    let direction = end - start;
+    let normal = direction.perp().normalize_or_zero();
+    let noise_offset = (start + end) * 0.37;
+    let steps = (direction.length() * 4.0).ceil().max(1.0) as i32;
+
+    let mut tiles: Vec<IVec2> = Vec::new();
+    for i in 0..=steps {
+        let t = i as f32 / steps as f32;
+        // the wiggle fades out towards both ends of the path
+        let wiggle = (value_noise(seed, noise_offset + Vec2::new(t * 3.0, 0.0)) - 0.5) * 2.0 * PATH_WIGGLE * (t * PI).sin();
+        let p = start + direction * t + normal * wiggle;
+        let tile_index = p.floor().as_ivec2();
+        if tiles.last() != Some(&tile_index) {
+            // keep the path connected without diagonal steps
+            if let Some(last) = tiles.last().copied() {
+                if last.x != tile_index.x && last.y != tile_index.y {
+                    tiles.push(IVec2::new(tile_index.x, last.y));
+                }
+            }
+            tiles.push(tile_index);
+        }
+    }
+    tiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cells of the chunk ordered by tile index, such that they can be compared
+    fn cells(seed: u64, chunk_index: IVec2) -> Vec<(IVec2, Option<Terrain>, Option<String>, bool)> {
+        let mut cells: Vec<_> = StructureRegistry::builtin()
+            .cells_in_chunk(seed, chunk_index)
+            .into_iter()
+            .map(|(tile_index, cell)| (tile_index, cell.terrain, cell.prefab, cell.entrance))
+            .collect();
+        cells.sort_by_key(|(tile_index, ..)| (tile_index.x, tile_index.y));
+        cells
+    }
+
+    fn chunks() -> impl Iterator<Item = IVec2> {
+        (-4..4).flat_map(|y| (-4..4).map(move |x| IVec2::new(x, y)))
+    }
+
+    #[test]
+    fn placement_only_depends_on_the_seed_and_the_chunk() {
+        for chunk_index in chunks() {
+            assert!(cells(1, chunk_index) == cells(1, chunk_index));
+        }
+        assert!(chunks().any(|chunk_index| cells(1, chunk_index) != cells(2, chunk_index)));
+    }
+
+    #[test]
+    fn cells_stay_within_their_chunk() {
+        let registry = StructureRegistry::builtin();
+        for seed in 0..8 {
+            for chunk_index in chunks() {
+                for tile_index in registry.cells_in_chunk(seed, chunk_index).keys() {
+                    assert_eq!(crate::chunk_index(*tile_index), chunk_index);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn start_of_the_player_is_kept_clear() {
+        let registry = StructureRegistry::builtin();
+        for seed in 0..64 {
+            for chunk_index in [IVec2::new(-1, -1), IVec2::new(0, -1), IVec2::new(-1, 0), IVec2::ZERO] {
+                let Some(placed) = registry.placed_in_chunk(seed, chunk_index) else {
+                    continue;
+                };
+                for (offset, _) in placed.structure.cells() {
+                    let tile_index = placed.origin + offset;
+                    assert!(tile_index.x.abs() > START_CLEARING || tile_index.y.abs() > START_CLEARING);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn paths_are_connected_and_the_same_from_both_ends() {
+        let (a, b) = (IVec2::new(-3, 5), IVec2::new(20, -7));
+        let path = winding_path(7, a, b);
+        assert_eq!(path, winding_path(7, b, a));
+        assert_eq!(path.first(), Some(&a));
+        assert_eq!(path.last(), Some(&b));
+        for step in path.windows(2) {
+            assert_eq!((step[1] - step[0]).abs().element_sum(), 1);
+        }
+    }
+}
@@ -1,43 +1,106 @@
-use glam::Vec2;
+use std::f32::consts::PI;
 
-use crate::{AbilityActivedEvent, math, event::Event};
-use super::Ctx;
+use glam::{Vec2, Vec3};
+use slotmap::DefaultKey;
 
+use crate::{AbilityActivedEvent, NoiseEvent, PrefabOverrides, SpawnEvent, WeaponDef, event::Event};
+use super::{ABILITY_NOISE_RADIUS, Ctx};
+
+/// Swings the weapon of the entity, hitting the entities within its reach and arc
+/// Weapons which do not hit multiple entities only hit the closest one, ranged weapons launch their projectile instead
 pub fn ability_activated_system(event: &AbilityActivedEvent, ctx: &mut dyn Ctx) {
     let world = ctx.world_mut();
     let entity_id = event.entity_id;
-    if let Some(e) = world.entities.get_mut(event.entity_id) {
-        let facing = e.facing;
-        let pos = e.pos;
-
-
-        let hit_length = 1.0;
-        let line1:(Vec2, Vec2) = (
-            Vec2::new(pos.x, pos.y),
-            Vec2::new(pos.x + facing.cos() * hit_length, pos.y + facing.sin() * hit_length)
-        );
-       
- 
-        //let target_pos = pos + Vec3::new(facing.cos(), facing.sin(), 0.0);
-        //let mut target_entity_id_opt = None;
-        for (other_entity_id, other_entity) in world.entities.iter() {
+    let weapon = world.weapon(entity_id).cloned().unwrap_or_default();
+    if let Some(prefab) = &weapon.projectile {
+        launch_projectile(ctx, entity_id, &weapon, prefab);
+        return;
+    }
+    let mut hits = Vec::new();
+    if let Some(e) = world.entities.get(event.entity_id) {
+        let facing = e.transform.facing;
+        let pos = e.transform.pos;
+        world.events.push_back(Event::Noise(NoiseEvent {
+            entity_id,
+            pos,
+            radius: ABILITY_NOISE_RADIUS,
+        }));
+
+        let forward = Vec2::new(facing.cos(), facing.sin());
+        let mut close_entities = Vec::new();
+        world.get_entities(e.tile_index(), weapon.reach + 1.0, &mut close_entities);
+        for other_entity_id in close_entities {
             if other_entity_id == event.entity_id {
                 continue;
             }
+            let Some(other_entity) = world.entity(other_entity_id) else {
+                continue;
+            };
+            // entities which cannot be damaged, such as campfires, do not absorb the swing
+            if !other_entity.is_alive() || !other_entity.health.as_ref().is_some_and(|health| health.can_receive_damage) {
+                continue;
+            }
+            let Some(other_body) = &other_entity.body else {
+                continue;
+            };
+
+            let to_other = (other_entity.transform.pos - pos).truncate();
+            let distance = to_other.length();
+            let r = other_body.radius;
+            if distance - r > weapon.reach {
+                continue;
+            }
 
-            let other_pos = other_entity.pos;
-            
-            let r = other_entity.radius;
-            let line2:(Vec2, Vec2) = (
-                Vec2::new(other_pos.x - facing.sin() * r, other_pos.y + facing.cos() * r),
-                Vec2::new(other_pos.x + facing.sin() * r, other_pos.y - facing.cos() * r)
-            );
-
-            if let Some(_intersection_point) = math::line_intersect(line1, line2) {
-                //world.events.push_back(Event::Despawn(DespawnEvent { entity_id: other_entity_id }));
-                ctx.push_event(Event::AbilityHit(crate::AbilityHitEvent { entity_id: entity_id, target_entity_id: other_entity_id }));
-                break;
+            // the arc is widened by the angle the body of the other entity covers
+            let half_width = if distance > r { (r / distance).asin() } else { PI };
+            if forward.angle_to(to_other).abs() > weapon.arc / 2.0 + half_width {
+                continue;
             }
+            hits.push((distance, other_entity_id));
+        }
+    }
+
+    hits.sort_by(|a, b| a.0.total_cmp(&b.0));
+    if !weapon.hits_multiple {
+        hits.truncate(1);
+    }
+    for (_, other_entity_id) in hits {
+        ctx.push_event(Event::AbilityHit(crate::AbilityHitEvent { entity_id, target_entity_id: other_entity_id }));
+    }
+}
+
+/// Launches the projectile of a ranged weapon towards the facing direction of the entity, using up its ammo
+/// The projectile starts within the body of the entity, which it never hits
+fn launch_projectile(ctx: &mut dyn Ctx, entity_id: DefaultKey, weapon: &WeaponDef, prefab: &str) {
+    let world = ctx.world_mut();
+    let Some(mut projectile) = world.prefabs.get(prefab).and_then(|prefab| prefab.projectile.clone()) else {
+        return;
+    };
+    let Some(entity) = world.entity_mut(entity_id) else {
+        return;
+    };
+    if let Some(ammo) = &weapon.ammo {
+        if !entity.inventory.as_mut().is_some_and(|inventory| inventory.remove(ammo, 1)) {
+            return;
         }
     }
+    let pos = entity.transform.pos;
+    let facing = entity.transform.facing;
+    projectile.velocity = Vec3::new(facing.cos(), facing.sin(), 0.0) * projectile.speed;
+    projectile.damage = weapon.damage;
+    projectile.owner = Some(entity_id);
+
+    ctx.push_event(Event::Noise(NoiseEvent {
+        entity_id,
+        pos,
+        radius: ABILITY_NOISE_RADIUS,
+    }));
+    ctx.push_event(Event::Spawn(SpawnEvent {
+        pos,
+        prefab: prefab.to_string(),
+        overrides: PrefabOverrides {
+            projectile: Some(projectile),
+            ..Default::default()
+        },
+    }));
 }
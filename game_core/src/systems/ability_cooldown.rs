@@ -1,28 +1,43 @@
-use crate::{AbilityActivedEvent, Frame, TickEvent, event::Event};
+use crate::{Ability, AbilityActivedEvent, Frame, TickEvent, event::Event};
 use super::Ctx;
 
 pub fn ability_cooldown_system(tick_event: &TickEvent, ctx: &mut dyn Ctx) {
     let dt = tick_event.dt;
     let world = ctx.world_mut();
     let mut entities = Vec::new();
-    world.entities(&mut entities);
+    world.query_ids::<Ability>(&mut entities);
     for entity_id in entities {
+        let weapon = world.weapon(entity_id).cloned();
         if let Some(e) = world.entities.get_mut(entity_id) {
-            if e.ability_timer_sec > 0.0 {
-                let ability_timer_before_sec = e.ability_timer_sec;
-                e.ability_timer_sec -= dt;
-                if ability_timer_before_sec > e.ability_activates_at_sec && e.ability_timer_sec <= e.ability_activates_at_sec {
+            let Some(ability) = &mut e.ability else {
+                continue;
+            };
+            if let Some(weapon) = &weapon {
+                // the timings change with the weapon between swings
+                if !ability.is_in_progress() {
+                    ability.equip(weapon);
+                }
+            }
+            if ability.timer_sec > 0.0 {
+                let ability_timer_before_sec = ability.timer_sec;
+                ability.timer_sec -= dt;
+                let activated = ability_timer_before_sec > ability.activates_at_sec && ability.timer_sec <= ability.activates_at_sec;
+                let finished = ability.timer_sec < 0.0;
+                if finished {
+                    ability.timer_sec = 0.0;
+                }
+
+                if activated {
                     world.events.push_back(Event::AbilityActived(AbilityActivedEvent {
                         entity_id,
                     }));
-                    e.frame = Frame::Attack;
+                    e.set_frame(Frame::Attack);
                 } 
-                if e.ability_timer_sec < 0.0 {
-                    e.ability_timer_sec = 0.0;
-                    e.frame = Frame::Default;
+                if finished {
+                    e.set_frame(Frame::Default);
                 } else {
-                    if e.frame != Frame::Attack {
-                        e.frame = Frame::ReadyAttack;
+                    if e.frame() != Frame::Attack {
+                        e.set_frame(Frame::ReadyAttack);
                     }
                 }
             }
@@ -1,61 +1,217 @@
-use glam::Vec3;
-use crate::{EntityVariant, TickEvent};
+use glam::{IVec2, Vec3};
+use crate::{Ai, AiFacts, AiState, EntityVariant, MAX_SEARCH_NODES, TickEvent, World, find_path};
 use super::Ctx;
 
-/// Bot AI system for zombies
-/// - Finds the player's position
-/// - Moves towards the player
-/// - Activates ability when in contact range with the player
-pub fn bot_system(_tick_event: &TickEvent, ctx: &mut dyn Ctx) {
+/// Time in seconds between planning new paths
+const REPLAN_INTERVAL_SEC: f32 = 0.5;
+
+/// Within this distance bots move straight towards the target instead of following a path
+const DIRECT_CHASE_DISTANCE: f32 = 1.5;
+
+/// Within this distance of the target position, bots stop moving
+const ARRIVE_DISTANCE: f32 = 0.1;
+
+/// Within this distance of the position a bot moves towards, it has arrived
+const ARRIVED_DISTANCE: f32 = 0.5;
+
+/// Maximum distance of the positions a wandering bot walks towards
+const WANDER_DISTANCE: f32 = 3.0;
+
+/// Speed in radians per second at which searching bots look around
+const SEARCH_TURN_SPEED: f32 = 2.0;
+
+/// Distance added to the radii of a bot and a building within which the bot touches the building
+const OBSTACLE_CONTACT_DISTANCE: f32 = 0.1;
+
+/// Bot AI system for entities with an ai component, such as zombies
+/// - Gathers facts about the bot and the player, such as whether the player is seen
+/// - Changes the state of the bot using the transitions of its behaviour
+/// - Acts according to the current state, e.g. chasing or attacking the player
+pub fn bot_system(tick_event: &TickEvent, ctx: &mut dyn Ctx) {
+    let dt = tick_event.dt;
     let world = ctx.world_mut();
-    
+
     let Some(player) = world.player() else { return };
-    let player_pos = player.pos;
-    let player_is_alive = player.health.is_alive();
-    if !player_is_alive  {
-        return; 
-    }
+    let player_pos = player.transform.pos;
+    let player_radius = player.body.as_ref().map(|body| body.radius).unwrap_or_default();
+    let player_is_alive = player.is_alive();
+    let light_radius = world.light_radius();
 
-    // Collect all zombie entity IDs
-    let mut zombie_ids = Vec::new();
-    for (entity_id, entity) in world.entities.iter() {
-        if matches!(entity.variant, EntityVariant::Zombie) {
-            zombie_ids.push(entity_id);
-        }
-    }
+    // Collect all bot entity IDs
+    let mut bot_ids = Vec::new();
+    world.query_ids::<Ai>(&mut bot_ids);
 
-    // Process each zombie
-    for zombie_id in zombie_ids {
-        let Some(zombie) = world.entities.get_mut(zombie_id) else {
+    for bot_id in bot_ids {
+        let Some(bot) = world.entities.get(bot_id) else {
             continue;
         };
-        if zombie.health.is_alive() == false {
-            // player is dead
-            // stop moving
-            zombie.move_dir = Vec3::ZERO;
+        let Some(ai) = &bot.ai else {
             continue;
+        };
+        let bot_pos = bot.transform.pos;
+        let bot_tile = bot.tile_index();
+        let bot_radius = bot.body.as_ref().map(|body| body.radius).unwrap_or_default();
+        let distance = bot_pos.distance(player_pos);
+
+        // Bots with perception know the last known position of the player,
+        // other bots always know where the player is
+        let (sees_target, target_pos) = match &bot.perception {
+            Some(perception) => (perception.sees_player, perception.last_known_pos),
+            None => (player_is_alive, player_is_alive.then_some(player_pos)),
+        };
+
+        let goal_pos = match ai.state {
+            AiState::Wander => ai.wander_target,
+            _ => target_pos,
+        };
+        let obstacle_pos = goal_pos.and_then(|goal_pos| find_obstacle(world, bot_pos, bot_radius, goal_pos));
+
+        let facts = AiFacts {
+            is_dead: !bot.is_alive(),
+            sees_target,
+            knows_target: target_pos.is_some(),
+            target_distance: distance,
+            target_in_contact: player_is_alive && distance <= bot_radius + player_radius,
+            in_light: player_is_alive && distance <= light_radius,
+            health_fraction: bot.health.as_ref().map(|h| h.current / h.max).unwrap_or(1.0),
+            state_time_sec: ai.state_timer_sec,
+            arrived: goal_pos.is_some_and(|goal_pos| bot_pos.distance(goal_pos) <= ARRIVED_DISTANCE),
+            obstacle_in_contact: obstacle_pos.is_some(),
+        };
+
+        // Change state, bots with an unknown behaviour keep their state
+        let next_state = world
+            .behaviours
+            .get(&ai.behaviour)
+            .and_then(|behaviour| behaviour.next_state(ai.state, &facts));
+        let state = next_state.unwrap_or(ai.state);
+        let wander_target = if next_state == Some(AiState::Wander) {
+            let angle = world.rng.next_f32() * std::f32::consts::TAU;
+            let distance = world.rng.next_f32() * WANDER_DISTANCE;
+            Some(bot_pos + Vec3::new(angle.cos(), angle.sin(), 0.0) * distance)
+        } else {
+            None
+        };
+        let Some(ai) = world.entities.get_mut(bot_id).and_then(|bot| bot.ai.as_mut()) else {
+            continue;
+        };
+        if next_state.is_some() {
+            ai.state = state;
+            ai.state_timer_sec = 0.0;
+            ai.wander_target = wander_target;
+            ai.path.clear();
+            ai.path_goal = None;
+        } else {
+            ai.state_timer_sec += dt;
+        }
+
+        // Act according to the state
+        let waypoint = match state {
+            AiState::Chase | AiState::Search => target_pos,
+            AiState::Wander => ai.wander_target,
+            _ => None,
+        }
+        .map(|goal_pos| {
+            if bot_pos.distance(goal_pos) > DIRECT_CHASE_DISTANCE {
+                let goal_tile = goal_pos.truncate().floor().as_ivec2();
+                next_waypoint(world, bot_id, bot_tile, goal_tile, dt).unwrap_or(goal_pos)
+            } else {
+                goal_pos
+            }
+        });
+
+        let Some(bot) = world.entities.get_mut(bot_id) else {
+            continue;
+        };
+        let mut move_dir = Vec3::ZERO;
+        match state {
+            AiState::Idle | AiState::Dead => {}
+            AiState::Attack => {
+                let direction = player_pos - bot_pos;
+                bot.transform.facing = direction.y.atan2(direction.x);
+                if let Some(ability) = &mut bot.ability {
+                    ability.activate();
+                }
+            }
+            AiState::Break => {
+                if let Some(obstacle_pos) = obstacle_pos {
+                    let direction = obstacle_pos - bot_pos;
+                    bot.transform.facing = direction.y.atan2(direction.x);
+                    if let Some(ability) = &mut bot.ability {
+                        ability.activate();
+                    }
+                }
+            }
+            AiState::Flee => {
+                if let Some(target_pos) = target_pos {
+                    move_dir = (bot_pos - target_pos).normalize_or_zero();
+                }
+            }
+            AiState::Chase | AiState::Search | AiState::Wander => {
+                if let Some(waypoint) = waypoint {
+                    let direction = waypoint - bot_pos;
+                    if direction.length() > ARRIVE_DISTANCE {
+                        move_dir = direction.normalize();
+                    } else if state == AiState::Search {
+                        // look around for the target
+                        bot.transform.facing += SEARCH_TURN_SPEED * dt;
+                    }
+                }
+            }
         }
 
-        let zombie_pos = zombie.pos;
-        let zombie_radius = zombie.radius;
-        // Calculate direction to player
-        let direction = player_pos - zombie_pos;
-        let distance = direction.length();
-
-        // Check if zombie is in contact range with player (touching)
-        let contact_range = zombie_radius + 0.4; // zombie radius + player radius (0.4)
-        if distance <= contact_range {
-            // Activate ability when touching player
-            zombie.active_ability();
-            // Stop moving when in contact
-            zombie.move_dir = Vec3::ZERO;
-        } else if distance > 0.0 {
-            // Move towards player
-            let normalized_dir = direction / distance;
-            zombie.move_dir = normalized_dir;
-            
-            // Update facing direction
-            zombie.facing = normalized_dir.y.atan2(normalized_dir.x);
+        if move_dir != Vec3::ZERO {
+            bot.transform.facing = move_dir.y.atan2(move_dir.x);
+        }
+        if let Some(mover) = &mut bot.mover {
+            mover.move_dir = move_dir;
         }
     }
 }
+
+/// Returns the position of a building touching the bot, which stands between the bot and its goal
+fn find_obstacle(world: &World, bot_pos: Vec3, bot_radius: f32, goal_pos: Vec3) -> Option<Vec3> {
+    let to_goal = (goal_pos - bot_pos).truncate();
+    let mut close_entities = Vec::new();
+    world.get_entities(bot_pos.truncate().floor().as_ivec2(), 1.0, &mut close_entities);
+    close_entities.into_iter().find_map(|entity_id| {
+        let e = world.entity(entity_id)?;
+        if e.variant != EntityVariant::Building || !e.is_alive() {
+            return None;
+        }
+        let body = e.body.as_ref().filter(|body| body.solid)?;
+        let to_obstacle = (e.transform.pos - bot_pos).truncate();
+        let in_contact = to_obstacle.length() <= bot_radius + body.radius + OBSTACLE_CONTACT_DISTANCE;
+        (in_contact && to_obstacle.dot(to_goal) > 0.0).then_some(e.transform.pos)
+    })
+}
+
+/// Returns the position of the next waypoint on the bot's path towards `goal`
+/// The cached path is planned again periodically, or right away when there is no path to a new goal
+/// Returns `None` if no path to the goal could be found
+fn next_waypoint(world: &mut World, entity_id: slotmap::DefaultKey, tile: IVec2, goal: IVec2, dt: f32) -> Option<Vec3> {
+    let ai = world.entities.get(entity_id)?.ai.as_ref()?;
+    let replan_timer_sec = ai.replan_timer_sec - dt;
+    let goal_changed = ai.path_goal != Some(goal);
+    let new_path = if replan_timer_sec <= 0.0 || (goal_changed && ai.path.is_empty()) {
+        Some(find_path(world, tile, goal, MAX_SEARCH_NODES))
+    } else {
+        None
+    };
+
+    let ai = world.entities.get_mut(entity_id)?.ai.as_mut()?;
+    ai.replan_timer_sec = replan_timer_sec;
+    if let Some(path) = new_path {
+        ai.replan_timer_sec = REPLAN_INTERVAL_SEC;
+        ai.path_goal = Some(goal);
+        ai.path = path.unwrap_or_default();
+    }
+
+    // skip waypoints which have been reached
+    while ai.path.first() == Some(&tile) {
+        ai.path.remove(0);
+    }
+
+    let waypoint = ai.path.first()?;
+    Some(Vec3::new(waypoint.x as f32 + 0.5, waypoint.y as f32 + 0.5, 0.0))
+}
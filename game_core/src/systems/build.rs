@@ -0,0 +1,38 @@
+use glam::Vec3;
+
+use crate::{BuildEvent, SpawnEvent, event::Event};
+use super::Ctx;
+
+/// Places the prefab of the item in the inventory slot onto the tile in front of the entity
+/// Ignored if the item cannot be placed or the tile is blocked, see [`crate::World::can_build_at`]
+pub fn build_system(event: &BuildEvent, ctx: &mut dyn Ctx) {
+    let world = ctx.world_mut();
+    let Some(entity) = world.entity(event.entity_id) else {
+        return;
+    };
+    if !entity.is_alive() {
+        return;
+    }
+    let Some(stack) = entity
+        .inventory
+        .as_ref()
+        .and_then(|inventory| inventory.slots.get(event.slot)?.as_ref())
+    else {
+        return;
+    };
+    let Some(prefab) = world.items.get(&stack.item).and_then(|def| def.places.clone()) else {
+        return;
+    };
+    let Some(tile_index) = world.build_target(event.entity_id) else {
+        return;
+    };
+    if !world.can_build_at(tile_index) {
+        return;
+    }
+
+    if let Some(inventory) = world.entity_mut(event.entity_id).and_then(|e| e.inventory.as_mut()) {
+        inventory.remove_from_slot(event.slot, 1);
+    }
+    let pos = Vec3::new(tile_index.x as f32 + 0.5, tile_index.y as f32 + 0.5, 0.0);
+    ctx.push_event(Event::Spawn(SpawnEvent::new(pos, &prefab)));
+}
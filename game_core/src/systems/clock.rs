@@ -0,0 +1,64 @@
+use glam::Vec3;
+
+use crate::{Ai, SpawnEvent, TickEvent, World, event::Event};
+use super::Ctx;
+
+/// Distance from the player at which zombies spawn at night, just outside of the drawn area
+const NIGHT_SPAWN_DISTANCE: f32 = 9.0;
+
+/// No zombies are spawned while this many living zombies are within the distance of the player
+const MAX_NEARBY_ZOMBIES: usize = 32;
+const NEARBY_ZOMBIE_DISTANCE: f32 = 16.0;
+
+/// Advances the time of day
+/// Time stands still once the player has died or has survived enough nights
+pub fn clock_system(event: &TickEvent, ctx: &mut dyn Ctx) {
+    let world = ctx.world_mut();
+    if world.player().is_none_or(|player| !player.is_alive()) || world.clock.has_won() {
+        return;
+    }
+    world.clock.tick(event.dt);
+}
+
+/// Spawns zombies around the player in the dark, at the spawn rate of the time of day
+pub fn night_spawn_system(event: &TickEvent, ctx: &mut dyn Ctx) {
+    let world = ctx.world_mut();
+    let rate = world.clock.zombie_spawn_rate();
+    if rate <= 0.0 || world.clock.has_won() {
+        return;
+    }
+    let Some(player) = world.player() else {
+        return;
+    };
+    if !player.is_alive() {
+        return;
+    }
+    let player_pos = player.transform.pos;
+    if nearby_zombies(world, player_pos) >= MAX_NEARBY_ZOMBIES {
+        return;
+    }
+
+    if ctx.rand_f32() >= rate * event.dt {
+        return;
+    }
+    let angle = ctx.rand_f32() * std::f32::consts::TAU;
+    let pos = player_pos + Vec3::new(angle.cos(), angle.sin(), 0.0) * NIGHT_SPAWN_DISTANCE;
+    let tile_index = pos.truncate().floor().as_ivec2();
+    let world = ctx.world_mut();
+    // only spawn on generated ground zombies can walk on
+    if world.tiles.get(tile_index).is_none_or(|tile| tile.is_solid()) {
+        return;
+    }
+    ctx.push_event(Event::Spawn(SpawnEvent::new(pos, "zombie")));
+}
+
+fn nearby_zombies(world: &World, pos: Vec3) -> usize {
+    world
+        .query::<Ai>()
+        .filter(|(entity_id, _)| {
+            world
+                .entity(*entity_id)
+                .is_some_and(|e| e.is_alive() && e.transform.pos.distance(pos) <= NEARBY_ZOMBIE_DISTANCE)
+        })
+        .count()
+}
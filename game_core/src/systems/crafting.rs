@@ -0,0 +1,248 @@
+use glam::Vec3;
+use slotmap::DefaultKey;
+
+use crate::{CraftEvent, Crafting, EntityVariant, Item, ItemStack, PrefabOverrides, SpawnEvent, TickEvent, World, event::Event};
+use super::Ctx;
+
+/// Distance within which the station of a recipe must be while crafting
+const STATION_DISTANCE: f32 = 2.0;
+
+/// Starts crafting the recipe, taking its inputs from the inventory of the entity
+/// Ignored if the entity is already crafting, lacks the inputs or is not near the station of the recipe
+pub fn craft_system(event: &CraftEvent, ctx: &mut dyn Ctx) {
+    let world = ctx.world_mut();
+    let Some(recipe) = world.recipes.get(&event.recipe).cloned() else {
+        return;
+    };
+    let Some(entity) = world.entity(event.entity_id) else {
+        return;
+    };
+    if !entity.is_alive() || entity.crafting.is_some() {
+        return;
+    }
+    if !entity.inventory.as_ref().is_some_and(|inventory| recipe.has_inputs(inventory)) {
+        return;
+    }
+    if let Some(station) = recipe.station {
+        if !is_station_nearby(world, entity.transform.pos, station) {
+            return;
+        }
+    }
+
+    let Some(entity) = world.entity_mut(event.entity_id) else {
+        return;
+    };
+    if let Some(inventory) = &mut entity.inventory {
+        for input in &recipe.inputs {
+            inventory.remove(&input.item, input.count);
+        }
+    }
+    entity.crafting = Some(Crafting {
+        recipe: event.recipe.clone(),
+        timer_sec: recipe.craft_sec,
+    });
+}
+
+/// Advances crafting, adding the output to the inventory when done
+/// Crafting is cancelled and the inputs are returned when walking away from the station
+pub fn crafting_system(event: &TickEvent, ctx: &mut dyn Ctx) {
+    let world = ctx.world_mut();
+    let mut entities = Vec::new();
+    world.query_ids::<Crafting>(&mut entities);
+    for entity_id in entities {
+        let Some(entity) = world.entity_mut(entity_id) else {
+            continue;
+        };
+        let Some(crafting) = &mut entity.crafting else {
+            continue;
+        };
+        crafting.timer_sec -= event.dt;
+        let done = crafting.timer_sec <= 0.0;
+        let recipe_id = crafting.recipe.clone();
+        let alive = entity.is_alive();
+        let pos = entity.transform.pos;
+
+        let Some(recipe) = world.recipes.get(&recipe_id).cloned() else {
+            // the recipe no longer exists
+            clear_crafting(world, entity_id);
+            continue;
+        };
+        if !alive {
+            clear_crafting(world, entity_id);
+            continue;
+        }
+        if let Some(station) = recipe.station {
+            if !is_station_nearby(world, pos, station) {
+                clear_crafting(world, entity_id);
+                for input in recipe.inputs {
+                    give(world, entity_id, input);
+                }
+                continue;
+            }
+        }
+        if done {
+            clear_crafting(world, entity_id);
+            give(world, entity_id, recipe.output);
+        }
+    }
+}
+
+fn clear_crafting(world: &mut World, entity_id: DefaultKey) {
+    if let Some(entity) = world.entity_mut(entity_id) {
+        entity.crafting = None;
+    }
+}
+
+/// Add items to the inventory of the entity, items which do not fit are dropped at its feet
+fn give(world: &mut World, entity_id: DefaultKey, stack: ItemStack) {
+    let max_stack = world.items.max_stack(&stack.item);
+    let Some(entity) = world.entity_mut(entity_id) else {
+        return;
+    };
+    let pos = entity.transform.pos;
+    let remaining = entity.inventory.as_mut().map(|inventory| inventory.add(&stack, max_stack)).unwrap_or(stack.count);
+    if remaining > 0 {
+        world.events.push_back(Event::Spawn(SpawnEvent {
+            pos,
+            prefab: "item".to_string(),
+            overrides: PrefabOverrides {
+                item: Some(Item {
+                    stack: ItemStack::new(&stack.item, remaining),
+                    pickup_delay_sec: 0.0,
+                }),
+                ..Default::default()
+            },
+        }));
+    }
+}
+
+/// Whether a living entity of the variant is within reach of the position
+fn is_station_nearby(world: &World, pos: Vec3, station: EntityVariant) -> bool {
+    let mut close_entities = Vec::new();
+    world.get_entities(pos.truncate().floor().as_ivec2(), STATION_DISTANCE, &mut close_entities);
+    close_entities.iter().any(|entity_id| {
+        world.entity(*entity_id).is_some_and(|e| {
+            e.variant == station && e.is_alive() && e.transform.pos.distance(pos) <= STATION_DISTANCE
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::IVec2;
+
+    use super::*;
+    use crate::{Entity, HeadlessCtx, Inventory, Tile, Transform, systems::map_entities_to_tiles_system};
+
+    const TICK: TickEvent = TickEvent { dt: 0.5 };
+
+    /// Open ground with a player carrying the items at the origin, and a campfire at the given position
+    fn ctx(items: &[(&str, u32)], campfire: Option<Vec3>) -> (HeadlessCtx, DefaultKey) {
+        let mut world = World::default();
+        for y in -4..16 {
+            for x in -4..16 {
+                world.tiles.insert(IVec2::new(x, y), Tile::default());
+            }
+        }
+        let mut inventory = Inventory::default();
+        for (item, count) in items {
+            inventory.add(&ItemStack::new(item, *count), 10);
+        }
+        let player = world.entities.insert(Entity {
+            variant: EntityVariant::Player,
+            transform: Transform { pos: Vec3::new(0.5, 0.5, 0.0), facing: 0.0 },
+            inventory: Some(inventory),
+            ..Default::default()
+        });
+        if let Some(pos) = campfire {
+            world.entities.insert(Entity {
+                variant: EntityVariant::Campfire,
+                transform: Transform { pos, facing: 0.0 },
+                ..Default::default()
+            });
+        }
+        let mut ctx = HeadlessCtx { world, frame_log: Vec::new() };
+        map_entities_to_tiles_system(&TICK, &mut ctx);
+        (ctx, player)
+    }
+
+    fn craft(ctx: &mut HeadlessCtx, entity_id: DefaultKey, recipe: &str) {
+        craft_system(&CraftEvent { entity_id, recipe: recipe.to_string() }, ctx);
+    }
+
+    fn count(ctx: &HeadlessCtx, entity_id: DefaultKey, item: &str) -> u32 {
+        ctx.world.entity(entity_id).unwrap().inventory.as_ref().unwrap().count(item)
+    }
+
+    #[test]
+    fn inputs_are_taken_and_the_output_is_received_when_done() {
+        let (mut ctx, player) = ctx(&[("wood", 3)], None);
+        craft(&mut ctx, player, "planks");
+        assert_eq!(count(&ctx, player, "wood"), 1);
+        assert!(ctx.world.entity(player).unwrap().crafting.is_some());
+
+        // planks take two seconds
+        for _ in 0..3 {
+            crafting_system(&TICK, &mut ctx);
+        }
+        assert_eq!(count(&ctx, player, "planks"), 0);
+        crafting_system(&TICK, &mut ctx);
+        assert_eq!(count(&ctx, player, "planks"), 1);
+        assert!(ctx.world.entity(player).unwrap().crafting.is_none());
+    }
+
+    #[test]
+    fn crafting_without_inputs_or_station_is_ignored() {
+        let (mut ctx, player) = ctx(&[("wood", 1), ("cloth", 1)], None);
+        craft(&mut ctx, player, "planks");
+        craft(&mut ctx, player, "torch");
+        craft(&mut ctx, player, "no_such_recipe");
+        assert!(ctx.world.entity(player).unwrap().crafting.is_none());
+        assert_eq!(count(&ctx, player, "wood"), 1);
+        assert_eq!(count(&ctx, player, "cloth"), 1);
+    }
+
+    #[test]
+    fn only_one_recipe_is_crafted_at_a_time() {
+        let (mut ctx, player) = ctx(&[("wood", 4)], None);
+        craft(&mut ctx, player, "planks");
+        craft(&mut ctx, player, "planks");
+        assert_eq!(count(&ctx, player, "wood"), 2);
+    }
+
+    #[test]
+    fn walking_away_from_the_station_cancels_and_refunds() {
+        let (mut ctx, player) = ctx(&[("wood", 1), ("cloth", 1)], Some(Vec3::new(1.5, 0.5, 0.0)));
+        craft(&mut ctx, player, "torch");
+        assert_eq!(count(&ctx, player, "wood"), 0);
+        crafting_system(&TICK, &mut ctx);
+        assert!(ctx.world.entity(player).unwrap().crafting.is_some());
+
+        ctx.world.entity_mut(player).unwrap().transform.pos = Vec3::new(10.5, 10.5, 0.0);
+        map_entities_to_tiles_system(&TICK, &mut ctx);
+        crafting_system(&TICK, &mut ctx);
+        assert!(ctx.world.entity(player).unwrap().crafting.is_none());
+        assert_eq!(count(&ctx, player, "wood"), 1);
+        assert_eq!(count(&ctx, player, "cloth"), 1);
+        assert_eq!(count(&ctx, player, "torch"), 0);
+    }
+
+    #[test]
+    fn output_which_does_not_fit_is_dropped() {
+        let (mut ctx, player) = ctx(&[("wood", 2)], None);
+        craft(&mut ctx, player, "planks");
+        let inventory = ctx.world.entity_mut(player).unwrap().inventory.as_mut().unwrap();
+        for slot in inventory.slots.iter_mut() {
+            *slot = Some(ItemStack::new("stone", 99));
+        }
+        for _ in 0..4 {
+            crafting_system(&TICK, &mut ctx);
+        }
+        assert_eq!(count(&ctx, player, "planks"), 0);
+        let dropped = ctx.world.events.iter().any(|event| match event {
+            Event::Spawn(spawn) => spawn.overrides.item.as_ref().is_some_and(|item| item.stack == ItemStack::new("planks", 1)),
+            _ => false,
+        });
+        assert!(dropped);
+    }
+}
@@ -1,28 +1,133 @@
-use crate::{ApplyDamageEvent, EntityVariant, Fade, Frame, systems::Ctx};
+use crate::{ApplyDamageEvent, DespawnEvent, DropEvent, EntityVariant, Event, Fade, Frame, systems::Ctx};
 
 pub fn damage_system(event: &ApplyDamageEvent, ctx: &mut dyn Ctx) {
     let world = ctx.world_mut();
     if let Some(entity) = world.entity_mut(event.entity_id) {
-        if entity.health.can_receive_damage {
-            entity.health.current -= event.damage_amount;
-            if entity.is_ability_in_progress() {
-                entity.reset_ability();
-                entity.frame = Frame::Default;
+        let Some(health) = &mut entity.health else {
+            return;
+        };
+        if !health.is_alive() {
+            // dead entities take no further damage
+            return;
+        }
+        if entity.mover.as_ref().is_some_and(|mover| mover.is_dodging()) {
+            // dodging entities cannot be hit
+            return;
+        }
+        if health.can_receive_damage {
+            health.current -= event.damage_amount;
+            let is_dead = health.current <= 0.0;
+            let despawn_on_death = health.despawn_on_death;
+            if is_dead {
+                health.current = 0.0;
+            }
+            if let Some(ability) = &mut entity.ability {
+                if ability.is_in_progress() {
+                    ability.reset();
+                    entity.set_frame(Frame::Default);
+                }
             }
 
-            if entity.health.current <= 0.0 {
-                entity.health.current = 0.0;
-                entity.solid = false;
-                entity.max_speed = 0.0;
+            if is_dead {
+                if let Some(body) = &mut entity.body {
+                    body.solid = false;
+                }
+                if let Some(mover) = &mut entity.mover {
+                    mover.max_speed = 0.0;
+                }
                 entity.variant = EntityVariant::Unknown;
-                entity.frame = Frame::Dead;
+                // felled entities fall over before they are dead, see the felling system
+                let frame = if entity.fellable.is_some() { Frame::Falling } else { Frame::Dead };
+                entity.set_frame(frame);
+
+                // the carried items fall to the ground
+                let slots = entity.inventory.as_ref().map(|inventory| inventory.slots.len()).unwrap_or_default();
+                for slot in 0..slots {
+                    world.events.push_back(Event::Drop(DropEvent {
+                        entity_id: event.entity_id,
+                        slot,
+                    }));
+                }
+                if despawn_on_death {
+                    world.events.push_back(Event::Despawn(DespawnEvent {
+                        entity_id: event.entity_id,
+                    }));
+                }
 
                 if world.player == event.entity_id {
                     world.start_fade(Fade::Out, 2.0);
                 }
-            } else {
-                entity.flash_timer.restart();
+            } else if let Some(sprite) = &mut entity.sprite {
+                sprite.flash_timer.restart();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::IVec2;
+    use slotmap::DefaultKey;
+
+    use super::*;
+    use crate::{Entity, HeadlessCtx, Health, Mover, TickEvent, Tile, World, systems::movement_system};
+
+    const TICK: TickEvent = TickEvent { dt: 0.1 };
+
+    /// Open ground with a player who dodges the next tick
+    fn ctx() -> (HeadlessCtx, DefaultKey) {
+        let mut world = World::default();
+        for y in -8..8 {
+            for x in -8..8 {
+                world.tiles.insert(IVec2::new(x, y), Tile::default());
             }
         }
+        world.player = world.entities.insert(Entity {
+            variant: EntityVariant::Player,
+            health: Some(Health::new(100.0)),
+            mover: Some(Mover { dodge: true, ..Default::default() }),
+            ..Default::default()
+        });
+        let player = world.player;
+        (HeadlessCtx { world, frame_log: Vec::new() }, player)
+    }
+
+    fn hit(ctx: &mut HeadlessCtx, entity_id: DefaultKey, damage_amount: f32) {
+        damage_system(&ApplyDamageEvent { entity_id, other_entity_id: DefaultKey::default(), damage_amount }, ctx);
+    }
+
+    fn health(ctx: &HeadlessCtx, entity_id: DefaultKey) -> f32 {
+        ctx.world.entity(entity_id).unwrap().health.as_ref().unwrap().current
+    }
+
+    #[test]
+    fn dodging_entities_take_no_damage() {
+        let (mut ctx, player) = ctx();
+        movement_system(&TICK, &mut ctx);
+        hit(&mut ctx, player, 10.0);
+        hit(&mut ctx, player, 1000.0);
+        assert_eq!(health(&ctx, player), 100.0);
+        assert!(ctx.world.entity(player).unwrap().is_alive());
+    }
+
+    #[test]
+    fn damage_is_taken_again_when_the_dodge_ends() {
+        let (mut ctx, player) = ctx();
+        // a dodge lasts a quarter of a second
+        for _ in 0..2 {
+            movement_system(&TICK, &mut ctx);
+        }
+        hit(&mut ctx, player, 10.0);
+        assert_eq!(health(&ctx, player), 100.0);
+        movement_system(&TICK, &mut ctx);
+        hit(&mut ctx, player, 10.0);
+        assert_eq!(health(&ctx, player), 90.0);
+    }
+
+    #[test]
+    fn entities_which_do_not_dodge_take_damage() {
+        let (mut ctx, player) = ctx();
+        hit(&mut ctx, player, 10.0);
+        assert_eq!(health(&ctx, player), 90.0);
     }
 }
@@ -5,7 +5,7 @@ pub fn despawn_system(event: &DespawnEvent, ctx: &mut dyn Ctx) {
     
     // Get the entity's position before removing it
     if let Some(entity) = world.entity(event.entity_id) {
-        let tile_pos = entity.pos.truncate().as_ivec2();
+        let tile_pos = entity.tile_index();
         
         // Remove entity from its tile
         if let Some(tile) = world.tiles.get_mut(tile_pos) {
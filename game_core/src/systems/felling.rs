@@ -0,0 +1,51 @@
+use crate::{DespawnEvent, Fellable, Frame, SpawnEvent, TickEvent, event::Event};
+use super::Ctx;
+
+/// Distance to solid entities within which a stump does not grow back, such that nothing gets stuck in the new tree
+const REGROW_CLEARANCE: f32 = 0.6;
+
+/// Felled entities fall over, leave a stump and eventually grow back
+pub fn felling_system(event: &TickEvent, ctx: &mut dyn Ctx) {
+    let world = ctx.world_mut();
+    let mut entities = Vec::new();
+    world.query_ids::<Fellable>(&mut entities);
+    let mut close_entities = Vec::new();
+    for entity_id in entities {
+        let Some(entity) = world.entity_mut(entity_id) else {
+            continue;
+        };
+        if entity.is_alive() {
+            continue;
+        }
+        let Some(fellable) = &mut entity.fellable else {
+            continue;
+        };
+        fellable.felled_sec += event.dt;
+        let frame = if fellable.felled_sec < fellable.fall_sec { Frame::Falling } else { Frame::Dead };
+        let regrow = fellable
+            .regrow_prefab
+            .clone()
+            .filter(|_| fellable.felled_sec >= fellable.regrow_sec);
+        entity.set_frame(frame);
+
+        let Some(prefab) = regrow else {
+            continue;
+        };
+        let pos = entity.transform.pos;
+        let tile_index = entity.tile_index();
+        close_entities.clear();
+        world.get_entities(tile_index, 1.0, &mut close_entities);
+        let blocked = close_entities.iter().any(|other_id| {
+            *other_id != entity_id
+                && world.entity(*other_id).is_some_and(|other| {
+                    let radius = other.body.as_ref().filter(|body| body.solid).map(|body| body.radius);
+                    radius.is_some_and(|radius| other.transform.pos.distance(pos) < radius + REGROW_CLEARANCE)
+                })
+        });
+        if blocked {
+            continue;
+        }
+        world.events.push_back(Event::Despawn(DespawnEvent { entity_id }));
+        world.events.push_back(Event::Spawn(SpawnEvent::new(pos, &prefab)));
+    }
+}
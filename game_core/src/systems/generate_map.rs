@@ -1,40 +1,85 @@
-use crate::{TickEvent, Tile, event::Event};
+use glam::{IVec2, Vec3};
+use crate::{Biome, CHUNK_LOAD_DISTANCE, CHUNK_UNLOAD_DISTANCE, Rng, SpawnEvent, Terrain, TickEvent, Tile, chunk_distance, chunk_index, chunk_seed, chunk_tiles, event::Event};
 use super::Ctx;
 
+/// Tiles within this distance of the origin are kept clear, such that the player can start moving
+const START_CLEARING: i32 = 2;
+
+/// Loads the chunks around the player and unloads distant chunks
+/// - Chunks which are loaded again are generated from the seed, and their entities restored
+/// - Chunks which are loaded for the first time are populated with trees and zombies,
+///   according to the biome of each tile, and the prefabs of structures
 pub fn generate_map_system(_: &TickEvent, ctx: &mut dyn Ctx) {
-    let player_id = ctx.world_mut().player;
-    if let Some(player) = ctx.world_mut().entities.get_mut(player_id) {
-        let grid_pos = player.pos.truncate().as_ivec2();
-        let s = 16;
-        for y in -s..=s {
-            for x in -s..=s {
-                let cell = grid_pos + glam::IVec2::new(x, y);
-                if ctx.world_mut().tiles.get(cell).is_none() {
-                    ctx.world_mut().tiles.insert(cell, Tile { solid: false, entities: Default::default() });
-                    let r = ctx.rand_unsigned(16);
-                    if r == 0 {
-                        // spawn a tree
-                        ctx.push_event(Event::Spawn(crate::event::SpawnEvent {
-                            pos: glam::Vec3::new(
-                                cell.x as f32 + 0.5,
-                                cell.y as f32 + 0.5,
-                                0.0,
-                            ),
-                            variant: crate::EntityVariant::Tree,
-                        }));
-                    } else if r == 1 {
-                        // spawn a zombie
-                        ctx.push_event(Event::Spawn(crate::event::SpawnEvent {
-                            pos: glam::Vec3::new(
-                                cell.x as f32 + 0.5,
-                                cell.y as f32 + 0.5,
-                                0.0,
-                            ),
-                            variant: crate::EntityVariant::Zombie,
-                        }));
-                    }
-                }
+    let world = ctx.world_mut();
+    let Some(player) = world.player() else { return };
+    let center = chunk_index(player.tile_index());
+
+    for y in -CHUNK_LOAD_DISTANCE..=CHUNK_LOAD_DISTANCE {
+        for x in -CHUNK_LOAD_DISTANCE..=CHUNK_LOAD_DISTANCE {
+            let index = center + IVec2::new(x, y);
+            if !ctx.world_mut().chunks.get(&index.into()).is_some_and(|chunk| chunk.loaded) {
+                load_chunk(index, ctx);
             }
         }
     }
+
+    let world = ctx.world_mut();
+    let far_chunks: Vec<IVec2> = world
+        .chunks
+        .iter()
+        .filter(|(index, chunk)| chunk.loaded && chunk_distance((**index).into(), center) > CHUNK_UNLOAD_DISTANCE)
+        .map(|(index, _)| (*index).into())
+        .collect();
+    if !far_chunks.is_empty() {
+        world.unload_chunks(&far_chunks);
+    }
+}
+
+fn load_chunk(index: IVec2, ctx: &mut dyn Ctx) {
+    let world = ctx.world_mut();
+    let seed = world.seed;
+    let mut rng = Rng::new(chunk_seed(world.seed, index));
+    let chunk = world.chunks.entry(index.into()).or_default();
+    chunk.loaded = true;
+    let populate = !chunk.populated;
+    chunk.populated = true;
+    world.load_chunk_entities(index);
+
+    let structure_cells = world.structures.cells_in_chunk(seed, index);
+    let mut spawns = Vec::new();
+    for cell in chunk_tiles(index) {
+        let biome = Biome::at(seed, cell);
+        let near_start = cell.abs().max_element() <= START_CLEARING;
+        let mut terrain = if near_start { Terrain::Grass } else { biome.terrain_at(seed, cell) };
+        let structure_cell = structure_cells.get(&cell);
+        if let Some(structure_terrain) = structure_cell.and_then(|c| c.terrain) {
+            terrain = structure_terrain;
+        }
+        world.tiles.insert(cell, Tile { terrain, biome, entities: Default::default() });
+        let r = rng.next_f32();
+        if !populate {
+            continue;
+        }
+        let pos = Vec3::new(cell.x as f32 + 0.5, cell.y as f32 + 0.5, 0.0);
+        if let Some(structure_cell) = structure_cell {
+            // structures replace the trees and zombies of the tile
+            if let Some(prefab) = &structure_cell.prefab {
+                spawns.push(SpawnEvent::new(pos, prefab));
+            }
+            continue;
+        }
+        if terrain.is_solid() || near_start {
+            continue;
+        }
+        if r < biome.tree_density() && terrain != Terrain::DirtPath {
+            // spawn a tree
+            spawns.push(SpawnEvent::new(pos, biome.tree_prefab()));
+        } else if r < biome.tree_density() + biome.zombie_density() {
+            // spawn a zombie
+            spawns.push(SpawnEvent::new(pos, "zombie"));
+        }
+    }
+    for spawn in spawns {
+        ctx.push_event(Event::Spawn(spawn));
+    }
 }
@@ -1,17 +1,59 @@
-use crate::PlayerInputEvent;
+use crate::{BuildEvent, ConsumeEvent, CraftEvent, DropEvent, PlayerInputEvent, RefuelEvent, SwitchWeaponEvent, event::Event};
 use super::Ctx;
 
 /// handles inputs for entities in the world
 pub fn input_system(e: &PlayerInputEvent, ctx: &mut dyn Ctx) {
     if let Some(entity) = ctx.world_mut().entities.get_mut(e.player_id) {
-        if entity.health.is_alive() == false {
+        if entity.is_alive() == false {
             // do not process input from dead entity
             return;
         }
-        entity.move_dir = e.move_dir;
-        entity.facing = e.facing;
+        entity.transform.facing = e.facing;
+        if let Some(mover) = &mut entity.mover {
+            mover.move_dir = e.move_dir;
+            mover.sprint = e.sprint;
+            if e.dodge {
+                mover.dodge = true;
+            }
+        }
         if e.use_ability {
-            entity.active_ability();
+            if let Some(ability) = &mut entity.ability {
+                ability.activate();
+            }
+        }
+        if let Some(slot) = e.drop_slot {
+            ctx.push_event(Event::Drop(DropEvent {
+                entity_id: e.player_id,
+                slot,
+            }));
+        }
+        if let Some(slot) = e.build_slot {
+            ctx.push_event(Event::Build(BuildEvent {
+                entity_id: e.player_id,
+                slot,
+            }));
+        }
+        if let Some(recipe) = &e.craft {
+            ctx.push_event(Event::Craft(CraftEvent {
+                entity_id: e.player_id,
+                recipe: recipe.clone(),
+            }));
+        }
+        if let Some(slot) = e.consume_slot {
+            ctx.push_event(Event::Consume(ConsumeEvent {
+                entity_id: e.player_id,
+                slot,
+            }));
+        }
+        if e.switch_weapon {
+            ctx.push_event(Event::SwitchWeapon(SwitchWeaponEvent {
+                entity_id: e.player_id,
+            }));
+        }
+        if e.refuel {
+            ctx.push_event(Event::Refuel(RefuelEvent {
+                entity_id: e.player_id,
+            }));
         }
     }
 }
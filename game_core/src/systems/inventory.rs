@@ -0,0 +1,133 @@
+use std::collections::BTreeSet;
+
+use glam::Vec3;
+
+use crate::{DropEvent, Inventory, Item, PickupEvent, PrefabOverrides, SpawnEvent, TickEvent, event::Event};
+use super::Ctx;
+
+/// Distance added to the radius of an entity at which it picks up items
+const PICKUP_RADIUS: f32 = 0.3;
+
+/// Distance in front of an entity at which dropped items are placed
+const DROP_DISTANCE: f32 = 1.0;
+
+/// Maximum random offset of dropped items
+const DROP_SCATTER: f32 = 0.2;
+
+/// Time in seconds before a dropped item can be picked up again
+const DROP_PICKUP_DELAY_SEC: f32 = 1.5;
+
+/// Living entities with an inventory pick up the items they walk over
+pub fn item_pickup_system(event: &TickEvent, ctx: &mut dyn Ctx) {
+    let world = ctx.world_mut();
+    for (_, item) in world.query_mut::<Item>() {
+        item.pickup_delay_sec = (item.pickup_delay_sec - event.dt).max(0.0);
+    }
+
+    let mut carriers = Vec::new();
+    world.query_ids::<Inventory>(&mut carriers);
+    let mut close_entities = Vec::new();
+    // an item is only picked up by one entity per tick
+    let mut picked_up = BTreeSet::new();
+    for entity_id in carriers {
+        let Some(entity) = world.entity(entity_id) else {
+            continue;
+        };
+        if entity.mover.is_none() || !entity.is_alive() {
+            continue;
+        }
+        let pos = entity.transform.pos;
+        let radius = entity.body.as_ref().map(|body| body.radius).unwrap_or_default() + PICKUP_RADIUS;
+
+        close_entities.clear();
+        world.get_entities(entity.tile_index(), 1.0, &mut close_entities);
+        for item_entity_id in close_entities.iter().copied() {
+            let Some(item_entity) = world.entity(item_entity_id) else {
+                continue;
+            };
+            let Some(item) = &item_entity.item else {
+                continue;
+            };
+            if item.pickup_delay_sec > 0.0 || picked_up.contains(&item_entity_id) {
+                continue;
+            }
+            if item_entity.transform.pos.truncate().distance(pos.truncate()) < radius {
+                picked_up.insert(item_entity_id);
+                world.events.push_back(Event::Pickup(PickupEvent {
+                    entity_id,
+                    item_entity_id,
+                }));
+            }
+        }
+    }
+}
+
+/// Moves the items into the inventory of the entity
+/// Items which do not fit are left on the ground
+pub fn pickup_system(event: &PickupEvent, ctx: &mut dyn Ctx) {
+    let world = ctx.world_mut();
+    let Some(stack) = world
+        .entity(event.item_entity_id)
+        .and_then(|e| e.item.as_ref())
+        .map(|item| item.stack.clone())
+    else {
+        return;
+    };
+    let max_stack = world.items.max_stack(&stack.item);
+    let Some(inventory) = world.entity_mut(event.entity_id).and_then(|e| e.inventory.as_mut()) else {
+        return;
+    };
+
+    let remaining = inventory.add(&stack, max_stack);
+    if remaining == 0 {
+        world.events.push_back(Event::Despawn(crate::DespawnEvent {
+            entity_id: event.item_entity_id,
+        }));
+        return;
+    }
+
+    let label = world.items.get(&stack.item).map(|def| def.label(remaining));
+    if let Some(item_entity) = world.entity_mut(event.item_entity_id) {
+        if let Some(item) = &mut item_entity.item {
+            item.stack.count = remaining;
+        }
+        if let (Some(sprite), Some(label)) = (&mut item_entity.sprite, label) {
+            sprite.floating_text = Some(label);
+        }
+    }
+}
+
+/// Spawns the stack of the inventory slot as an item in front of the entity
+/// Items are dropped at the feet of the entity when the tile in front of it is solid
+pub fn drop_system(event: &DropEvent, ctx: &mut dyn Ctx) {
+    let world = ctx.world_mut();
+    let Some(entity) = world.entity_mut(event.entity_id) else {
+        return;
+    };
+    let pos = entity.transform.pos;
+    let facing = entity.transform.facing;
+    let Some(stack) = entity.inventory.as_mut().and_then(|inventory| inventory.take(event.slot)) else {
+        return;
+    };
+
+    let front = pos + Vec3::new(facing.cos(), facing.sin(), 0.0) * DROP_DISTANCE;
+    let drop_pos = if world.is_tile_solid(front.truncate().floor().as_ivec2()) {
+        pos
+    } else {
+        front
+    };
+    // scatter the items a bit, such that several drops do not end up on top of each other
+    let drop_pos = drop_pos + Vec3::new(ctx.rand_f32_range(-DROP_SCATTER, DROP_SCATTER), ctx.rand_f32_range(-DROP_SCATTER, DROP_SCATTER), 0.0);
+
+    ctx.push_event(Event::Spawn(SpawnEvent {
+        pos: drop_pos,
+        prefab: "item".to_string(),
+        overrides: PrefabOverrides {
+            item: Some(Item {
+                stack,
+                pickup_delay_sec: DROP_PICKUP_DELAY_SEC,
+            }),
+            ..Default::default()
+        },
+    }));
+}
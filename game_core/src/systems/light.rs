@@ -0,0 +1,61 @@
+use crate::{LightSource, RefuelEvent, TickEvent};
+use super::Ctx;
+
+/// Lights flicker and the lights of living entities burn their fuel
+pub fn light_system(event: &TickEvent, ctx: &mut dyn Ctx) {
+    let world = ctx.world_mut();
+    let mut entities = Vec::new();
+    world.query_ids::<LightSource>(&mut entities);
+    for entity_id in entities {
+        let Some(entity) = world.entity_mut(entity_id) else {
+            continue;
+        };
+        let is_alive = entity.is_alive();
+        let Some(light_source) = &mut entity.light_source else {
+            continue;
+        };
+        light_source.flicker_sec += event.dt;
+        if is_alive && light_source.burns_fuel {
+            light_source.fuel_sec = (light_source.fuel_sec - event.dt).max(0.0);
+        }
+    }
+}
+
+/// Burns the fuel item giving the least fuel from the inventory of the entity, such that wood is used before torches
+/// Ignored if the light is full or there is nothing to burn
+pub fn refuel_system(event: &RefuelEvent, ctx: &mut dyn Ctx) {
+    let world = ctx.world_mut();
+    let Some(entity) = world.entity(event.entity_id) else {
+        return;
+    };
+    if !entity.is_alive() {
+        return;
+    }
+    let (Some(inventory), Some(light_source)) = (&entity.inventory, &entity.light_source) else {
+        return;
+    };
+    if !light_source.burns_fuel || light_source.fuel_sec >= light_source.max_fuel_sec {
+        return;
+    }
+    let fuel = inventory
+        .slots
+        .iter()
+        .enumerate()
+        .filter_map(|(slot, stack)| {
+            let fuel_sec = world.items.get(&stack.as_ref()?.item)?.fuel_sec?;
+            Some((slot, fuel_sec))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+    let Some((slot, fuel_sec)) = fuel else {
+        return;
+    };
+
+    let Some(entity) = world.entity_mut(event.entity_id) else {
+        return;
+    };
+    if let (Some(inventory), Some(light_source)) = (&mut entity.inventory, &mut entity.light_source) {
+        if inventory.remove_from_slot(slot, 1) {
+            light_source.refuel(fuel_sec);
+        }
+    }
+}
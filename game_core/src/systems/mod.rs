@@ -15,6 +15,16 @@ mod ability_activated;
 mod ability_cooldown;
 mod damage;
 mod tick;
+mod perception;
+mod inventory;
+mod felling;
+mod crafting;
+mod build;
+mod clock;
+mod light;
+mod survival;
+mod weapon;
+mod projectile;
 
 pub use ability_activated::ability_activated_system;
 pub use ability_cooldown::ability_cooldown_system;
@@ -27,6 +37,16 @@ pub use restart::restart_system;
 pub use spawn::spawn_system;
 pub use despawn::despawn_system;
 pub use bot::bot_system;
+pub use felling::felling_system;
+pub use crafting::{craft_system, crafting_system};
+pub use build::build_system;
+pub use clock::{clock_system, night_spawn_system};
+pub use light::{light_system, refuel_system};
+pub use survival::{survival_system, consume_system};
+pub use weapon::switch_weapon_system;
+pub use projectile::projectile_system;
+pub use inventory::{item_pickup_system, pickup_system, drop_system};
+pub use perception::{perception_system, noise_system, FOOTSTEP_NOISE_RADIUS, ABILITY_NOISE_RADIUS};
 
 use glam::{Vec2, Vec3, Vec4};
 
@@ -35,7 +55,10 @@ pub trait Ctx {
     fn push_event(&mut self, event: Event) {
         self.world_mut().events.push_back(event);
     }
-    fn rand_u32(&mut self) -> u32;
+    /// Returns the next random number from the world's seeded random number generator
+    fn rand_u32(&mut self) -> u32 {
+        self.world_mut().rng.next_u32()
+    }
     fn rand_unsigned(&mut self, max: u32) -> u32 {
         self.rand_u32() % max
     }
@@ -59,12 +82,21 @@ pub fn process(ctx: &mut dyn Ctx) {
     while let Some(event) = ctx.world_mut().events.pop_front() {
         match event {
             Event::Tick(tick_event) => {
+                clock_system(&tick_event, ctx);
                 generate_map_system(&tick_event, ctx);
                 map_entities_to_tiles_system(&tick_event, ctx);
                 tick_system(&tick_event, ctx);
+                perception_system(&tick_event, ctx);
                 bot_system(&tick_event, ctx);
                 movement_system(&tick_event, ctx);
+                projectile_system(&tick_event, ctx);
+                item_pickup_system(&tick_event, ctx);
                 ability_cooldown_system(&tick_event, ctx);
+                felling_system(&tick_event, ctx);
+                crafting_system(&tick_event, ctx);
+                light_system(&tick_event, ctx);
+                survival_system(&tick_event, ctx);
+                night_spawn_system(&tick_event, ctx);
             }
             Event::Collision(collision_event) => {
                 collision_system(&collision_event, ctx);
@@ -93,6 +125,30 @@ pub fn process(ctx: &mut dyn Ctx) {
             Event::DamageEntity(damage_entity_event) => {
                 damage_system(&damage_entity_event, ctx);
             },
+            Event::Noise(noise_event) => {
+                noise_system(&noise_event, ctx);
+            },
+            Event::Pickup(pickup_event) => {
+                pickup_system(&pickup_event, ctx);
+            },
+            Event::Drop(drop_event) => {
+                drop_system(&drop_event, ctx);
+            },
+            Event::Craft(craft_event) => {
+                craft_system(&craft_event, ctx);
+            },
+            Event::Build(build_event) => {
+                build_system(&build_event, ctx);
+            },
+            Event::Refuel(refuel_event) => {
+                refuel_system(&refuel_event, ctx);
+            },
+            Event::Consume(consume_event) => {
+                consume_system(&consume_event, ctx);
+            },
+            Event::SwitchWeapon(switch_weapon_event) => {
+                switch_weapon_system(&switch_weapon_event, ctx);
+            },
         }
     }
 
@@ -1,41 +1,61 @@
-use std::collections::HashMap;
-use crate::{CollisionEvent, Frame, TickEvent, event::Event};
+use std::collections::BTreeMap;
+use glam::{Vec2, Vec3};
+use crate::{CollisionEvent, Entity, Frame, Mover, NoiseEvent, TickEvent, World, event::Event, math};
+use super::FOOTSTEP_NOISE_RADIUS;
 use super::Ctx;
 
-/// handles movement of entities in the world
-/// also handled collision resolution
+/// Stamina used per second of sprinting
+const SPRINT_STAMINA_PER_SEC: f32 = 25.0;
+
+/// Stamina used by a single dodge
+const DODGE_STAMINA: f32 = 30.0;
+
+/// Multiplier of the footstep noise radius while sprinting
+const SPRINT_NOISE_MULTIPLIER: f32 = 1.5;
+
+/// handles movement of entities in the world, including sprinting and dodging
+/// also handled collision resolution against solid entities and solid tiles
 pub fn movement_system(tick_event: &TickEvent, ctx: &mut dyn Ctx) {
     let dt = tick_event.dt;
     let world = ctx.world_mut();
     let mut entities = Vec::new();
-    world.entities(&mut entities);
+    world.query_ids::<Mover>(&mut entities);
     let mut close_entities = Vec::new();
-    let mut colliding_entities = HashMap::new();
+    let mut colliding_entities = BTreeMap::new();
     for entity_id in entities {
         close_entities.clear();
         colliding_entities.clear();
         let Some(entity) = world.entity(entity_id) else {
             continue;
         };
-        let entity_vel = entity.move_dir * dt * entity.max_speed;
-        let entity_pos = entity.pos;
-        let entity_solid = entity.solid;
-        let entity_radius = entity.radius;
         let entity_tile_index = entity.tile_index();
+        let terrain = world.terrain(entity_tile_index);
+        let Some((velocity, sprinting)) = world.entity_mut(entity_id).and_then(|e| velocity(e, dt)) else {
+            continue;
+        };
+        let Some(entity) = world.entity(entity_id) else {
+            continue;
+        };
+        let entity_vel = velocity * dt * terrain.speed_multiplier();
+        let entity_pos = entity.transform.pos;
+        let entity_solid = entity.body.as_ref().is_some_and(|body| body.solid);
+        let entity_radius = entity.body.as_ref().map(|body| body.radius).unwrap_or_default();
 
         if entity_vel.length() == 0.0 {
             if let Some(entity_mut) = world.entities.get_mut(entity_id) {
                 if entity_mut.is_ability_in_progress() == false {
-                    if entity_mut.health.is_alive() {
-                        entity_mut.frame = Frame::Default;
+                    if entity_mut.is_alive() {
+                        entity_mut.set_frame(Frame::Default);
                     }
                 }
-                if entity_mut.move_sinus != 0.0 {
-                    entity_mut.move_sinus /= 2.0;
+                if let Some(mover) = &mut entity_mut.mover {
+                    if mover.move_sinus != 0.0 {
+                        mover.move_sinus /= 2.0;
 
-                    if entity_mut.move_sinus.abs() < 0.01 {
-                        entity_mut.move_sinus = 0.0;
-                        entity_mut.move_distance_total = 0.0;
+                        if mover.move_sinus.abs() < 0.01 {
+                            mover.move_sinus = 0.0;
+                            mover.move_distance_total = 0.0;
+                        }
                     }
                 }
             }
@@ -52,7 +72,7 @@ pub fn movement_system(tick_event: &TickEvent, ctx: &mut dyn Ctx) {
 
         // check and resolve collision with solid entities
         if entity_solid {
-            world.get_entities(entity_pos.truncate().as_ivec2(), 2.0, &mut close_entities);
+            world.get_entities(entity_pos.truncate().floor().as_ivec2(), 2.0, &mut close_entities);
         }
 
         for other_entity_id in &close_entities {
@@ -62,13 +82,16 @@ pub fn movement_system(tick_event: &TickEvent, ctx: &mut dyn Ctx) {
             let Some(other_entity) = world.entity(*other_entity_id) else {
                 continue;
             };
-            if !other_entity.solid {
+            let Some(other_body) = &other_entity.body else {
+                continue;
+            };
+            if !other_body.solid {
                 continue;
             }
 
-            let to_other = other_entity.pos - entity_pos;
+            let to_other = other_entity.transform.pos - entity_pos;
             let dist = to_other.length();
-            let min_dist = entity_radius + other_entity.radius;
+            let min_dist = entity_radius + other_body.radius;
          
             if dist < min_dist && dist > 0.0 {
                 let overlap = min_dist - dist;
@@ -78,28 +101,47 @@ pub fn movement_system(tick_event: &TickEvent, ctx: &mut dyn Ctx) {
             }
         }
 
+        // resolve collision with solid tiles, sliding along them
+        if entity_solid {
+            entity_pos = resolve_tile_collision(world, entity_pos, entity_radius);
+        }
+
         // finally update entity position
+        let mut footstep = false;
         if let Some(entity_mut) = world.entities.get_mut(entity_id) {
-            let old_pos = entity_mut.pos;
+            let old_pos = entity_mut.transform.pos;
             let moved_distance = (entity_pos - old_pos).length();
-            entity_mut.move_distance_total += moved_distance * entity_mut.move_sinus_speed;
-            entity_mut.pos = entity_pos;
-            let move_sinus = entity_mut.move_sinus;
-            entity_mut.move_sinus = entity_mut.move_distance_total.sin();
-            if move_sinus.signum() != entity_mut.move_sinus.signum() {
-                // TODO seperate from this system
-                if entity_mut.is_ability_in_progress() == false {
-                    if move_sinus < 0.0 {
-                        entity_mut.frame = Frame::Walk1;
-                    } else {
-                        entity_mut.frame = Frame::Walk2;
+            entity_mut.transform.pos = entity_pos;
+            let ability_in_progress = entity_mut.is_ability_in_progress();
+            if let Some(mover) = &mut entity_mut.mover {
+                mover.move_distance_total += moved_distance * mover.move_sinus_speed;
+                let move_sinus = mover.move_sinus;
+                mover.move_sinus = mover.move_distance_total.sin();
+                if move_sinus.signum() != mover.move_sinus.signum() {
+                    footstep = true;
+                    // TODO seperate from this system
+                    if ability_in_progress == false {
+                        if move_sinus < 0.0 {
+                            entity_mut.set_frame(Frame::Walk1);
+                        } else {
+                            entity_mut.set_frame(Frame::Walk2);
+                        }
                     }
                 }
             }
         }
 
+        if footstep {
+            let noise = if sprinting { SPRINT_NOISE_MULTIPLIER } else { 1.0 };
+            world.events.push_back(Event::Noise(NoiseEvent {
+                entity_id,
+                pos: entity_pos,
+                radius: FOOTSTEP_NOISE_RADIUS * terrain.footstep_noise() * noise,
+            }));
+        }
+
         // add entity to new tile
-        let new_tile_index = entity_pos.truncate().as_ivec2();
+        let new_tile_index = entity_pos.truncate().floor().as_ivec2();
         if let Some(tile) = world.tiles.get_mut(new_tile_index) {
             tile.entities.insert(entity_id, ());
         }
@@ -112,3 +154,61 @@ pub fn movement_system(tick_event: &TickEvent, ctx: &mut dyn Ctx) {
         }
     }
 }
+
+/// Velocity of an entity with a mover and whether it is sprinting
+/// Starts a requested dodge and pays for dodging and sprinting using the stamina of the entity,
+/// entities without survival stats dodge and sprint for free
+fn velocity(entity: &mut Entity, dt: f32) -> Option<(Vec3, bool)> {
+    let mover = entity.mover.as_mut()?;
+    if mover.dodge {
+        mover.dodge = false;
+        let paid = !mover.is_dodging() && entity.survival.as_mut().is_none_or(|survival| survival.use_stamina(DODGE_STAMINA));
+        if paid {
+            // dodge backwards when standing still
+            let facing = entity.transform.facing;
+            let backwards = Vec3::new(-facing.cos(), -facing.sin(), 0.0);
+            mover.dodge_dir = mover.move_dir.try_normalize().unwrap_or(backwards);
+            mover.dodge_timer_sec = mover.dodge_sec;
+        }
+    }
+    if mover.is_dodging() {
+        mover.dodge_timer_sec -= dt;
+        return Some((mover.dodge_dir * mover.dodge_speed, false));
+    }
+
+    let mut speed = mover.max_speed;
+    let sprinting = mover.sprint && mover.move_dir != Vec3::ZERO && match &mut entity.survival {
+        Some(survival) if survival.can_sprint() => {
+            survival.stamina = (survival.stamina - SPRINT_STAMINA_PER_SEC * dt).max(0.0);
+            survival.exertion_timer_sec = 0.0;
+            true
+        }
+        Some(_) => false,
+        None => true,
+    };
+    if sprinting {
+        speed *= mover.sprint_multiplier;
+    }
+    Some((mover.move_dir * speed, sprinting))
+}
+
+/// Pushes a circle at the given position out of all nearby solid tiles
+fn resolve_tile_collision(world: &World, pos: Vec3, radius: f32) -> Vec3 {
+    let mut pos = pos;
+    let tile_index = pos.truncate().floor().as_ivec2();
+    let s = radius.ceil() as i32;
+    for y in -s..=s {
+        for x in -s..=s {
+            let cell = tile_index + glam::IVec2::new(x, y);
+            if !world.is_tile_solid(cell) {
+                continue;
+            }
+            let min = cell.as_vec2();
+            let max = min + Vec2::ONE;
+            if let Some(correction) = math::circle_aabb_penetration(pos.truncate(), radius, min, max) {
+                pos += correction.extend(0.0);
+            }
+        }
+    }
+    pos
+}
@@ -0,0 +1,169 @@
+use std::f32::consts::{PI, TAU};
+
+use crate::{NoiseEvent, Perception, TickEvent};
+use super::Ctx;
+
+/// Distance at which footsteps can be heard
+pub const FOOTSTEP_NOISE_RADIUS: f32 = 3.0;
+
+/// Distance at which using an ability, such as swinging an axe, can be heard
+pub const ABILITY_NOISE_RADIUS: f32 = 6.0;
+
+/// Updates what bots know about the player
+/// - sees the player within the sight cone
+/// - notices the player when standing within the player's light
+/// - forgets the player when not detected for a while
+pub fn perception_system(tick_event: &TickEvent, ctx: &mut dyn Ctx) {
+    let dt = tick_event.dt;
+    let world = ctx.world_mut();
+    let Some(player) = world.player() else { return };
+    let player_pos = player.transform.pos;
+    let player_is_alive = player.is_alive();
+
+    // the light radius is the reach of the player's light
+    let light_radius = world.light_radius();
+    // zombies see further at night
+    let aggression = world.clock.aggression();
+
+    let mut entities = Vec::new();
+    world.query_ids::<Perception>(&mut entities);
+    for entity_id in entities {
+        let Some(e) = world.entity(entity_id) else {
+            continue;
+        };
+        let Some(perception) = &e.perception else {
+            continue;
+        };
+        let pos = e.transform.pos;
+        let to_player = player_pos - pos;
+        let distance = to_player.length();
+
+        // angle between facing and the direction to the player, in the range -PI..PI
+        let angle = (to_player.y.atan2(to_player.x) - e.transform.facing + PI).rem_euclid(TAU) - PI;
+        let in_sight_cone = distance <= perception.sight_range * aggression && angle.abs() <= perception.sight_angle;
+        let in_light = distance <= light_radius;
+        let sees_player = player_is_alive && (in_sight_cone || in_light) && world.line_of_sight(pos, player_pos);
+
+        let Some(perception) = world.entity_mut(entity_id).and_then(|e| e.perception.as_mut()) else {
+            continue;
+        };
+        perception.sees_player = sees_player;
+        if sees_player {
+            perception.detect(player_pos);
+        } else {
+            perception.time_since_detected_sec += dt;
+            if perception.time_since_detected_sec > perception.memory_sec {
+                perception.last_known_pos = None;
+            }
+        }
+    }
+}
+
+/// Lets bots hear noises made by the player
+pub fn noise_system(event: &NoiseEvent, ctx: &mut dyn Ctx) {
+    let world = ctx.world_mut();
+    if event.entity_id != world.player {
+        return;
+    }
+    // zombies hear further at night
+    let aggression = world.clock.aggression();
+
+    for (_, e) in world.entities.iter_mut() {
+        let pos = e.transform.pos;
+        let Some(perception) = &mut e.perception else {
+            continue;
+        };
+        if pos.distance(event.pos) <= event.radius * perception.hearing * aggression {
+            perception.detect(event.pos);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use super::*;
+    use crate::{DAY_LENGTH_SEC, Entity, EntityVariant, HeadlessCtx, Transform, World};
+
+    const TICK: TickEvent = TickEvent { dt: 0.5 };
+
+    /// A zombie at the origin facing along x, in the dark where it sees 6 tiles ahead and the light reaches 1.5 tiles
+    fn ctx() -> HeadlessCtx {
+        let mut world = World::default();
+        world.clock.time_sec = 0.8 * DAY_LENGTH_SEC;
+        world.player = world.entities.insert(Entity {
+            variant: EntityVariant::Player,
+            ..Default::default()
+        });
+        world.entities.insert(Entity {
+            variant: EntityVariant::Zombie,
+            perception: Some(Perception::default()),
+            ..Default::default()
+        });
+        HeadlessCtx { world, frame_log: Vec::new() }
+    }
+
+    fn set_player_pos(ctx: &mut HeadlessCtx, pos: Vec3) {
+        let player = ctx.world.player;
+        ctx.world.entity_mut(player).unwrap().transform = Transform { pos, facing: 0.0 };
+    }
+
+    fn perception(ctx: &HeadlessCtx) -> &Perception {
+        ctx.world.entities.values().find_map(|e| e.perception.as_ref()).unwrap()
+    }
+
+    fn sees(pos: Vec3) -> bool {
+        let mut ctx = ctx();
+        set_player_pos(&mut ctx, pos);
+        perception_system(&TICK, &mut ctx);
+        perception(&ctx).sees_player
+    }
+
+    #[test]
+    fn player_is_seen_within_the_cone_and_range() {
+        assert!(sees(Vec3::new(5.0, 0.0, 0.0)));
+        assert!(sees(Vec3::new(3.0, 2.0, 0.0)));
+        assert!(!sees(Vec3::new(7.0, 0.0, 0.0)));
+        assert!(!sees(Vec3::new(0.0, 5.0, 0.0)));
+        assert!(!sees(Vec3::new(-5.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn player_is_seen_within_the_light_from_any_side() {
+        assert!(sees(Vec3::new(-1.0, 0.0, 0.0)));
+        assert!(sees(Vec3::new(0.0, -1.0, 0.0)));
+        assert!(!sees(Vec3::new(-2.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn player_is_forgotten_when_not_detected_for_a_while() {
+        let mut ctx = ctx();
+        set_player_pos(&mut ctx, Vec3::new(5.0, 0.0, 0.0));
+        perception_system(&TICK, &mut ctx);
+        assert_eq!(perception(&ctx).last_known_pos, Some(Vec3::new(5.0, 0.0, 0.0)));
+
+        // memory lasts five seconds
+        set_player_pos(&mut ctx, Vec3::new(-5.0, 0.0, 0.0));
+        for _ in 0..10 {
+            perception_system(&TICK, &mut ctx);
+        }
+        assert!(!perception(&ctx).sees_player);
+        assert_eq!(perception(&ctx).last_known_pos, Some(Vec3::new(5.0, 0.0, 0.0)));
+        perception_system(&TICK, &mut ctx);
+        assert_eq!(perception(&ctx).last_known_pos, None);
+    }
+
+    fn hears(pos: Vec3) -> bool {
+        let mut ctx = ctx();
+        let player = ctx.world.player;
+        noise_system(&NoiseEvent { entity_id: player, pos, radius: FOOTSTEP_NOISE_RADIUS }, &mut ctx);
+        perception(&ctx).last_known_pos == Some(pos)
+    }
+
+    #[test]
+    fn footsteps_of_the_player_are_heard_further_at_night() {
+        assert!(hears(Vec3::new(-4.0, 0.0, 0.0)));
+        assert!(!hears(Vec3::new(-5.0, 0.0, 0.0)));
+    }
+}
@@ -0,0 +1,126 @@
+use glam::Vec3;
+use slotmap::DefaultKey;
+
+use crate::{ApplyDamageEvent, DespawnEvent, Item, ItemStack, NoiseEvent, PrefabOverrides, Projectile, SpawnEvent, TickEvent, World, event::Event, math};
+use super::Ctx;
+
+/// Distance at which the impact of a projectile can be heard
+const IMPACT_NOISE_RADIUS: f32 = 4.0;
+
+/// Moves projectiles along their velocity, sweeping them against solid bodies and solid tiles
+/// such that fast projectiles do not pass through thin entities between two ticks
+/// A projectile hitting an entity damages it, the projectile is removed once it hits something or its lifetime runs out
+pub fn projectile_system(event: &TickEvent, ctx: &mut dyn Ctx) {
+    let dt = event.dt;
+    let world = ctx.world_mut();
+    let mut entities = Vec::new();
+    world.query_ids::<Projectile>(&mut entities);
+    let mut close_entities = Vec::new();
+    for entity_id in entities {
+        let Some(entity) = world.entity(entity_id) else {
+            continue;
+        };
+        let Some(projectile) = &entity.projectile else {
+            continue;
+        };
+        let from = entity.transform.pos;
+        let to = from + projectile.velocity * dt;
+
+        close_entities.clear();
+        world.get_entities(entity.tile_index(), (to - from).length() + 1.0, &mut close_entities);
+        let hit_entity = close_entities
+            .iter()
+            .filter_map(|other_entity_id| {
+                if *other_entity_id == entity_id || Some(*other_entity_id) == projectile.owner {
+                    return None;
+                }
+                let other_entity = world.entity(*other_entity_id)?;
+                if !other_entity.is_alive() {
+                    return None;
+                }
+                // projectiles fly over bodies which can be walked through, such as campfires
+                let body = other_entity.body.as_ref().filter(|body| body.solid)?;
+                let radius = body.radius + projectile.radius;
+                let t = math::segment_circle_intersect(from.truncate(), to.truncate(), other_entity.transform.pos.truncate(), radius)?;
+                Some((t, *other_entity_id))
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+        let hit_tile = math::first_cell_hit(from.truncate(), to.truncate(), |cell| world.is_tile_solid(cell));
+
+        let (t, target) = match (hit_entity, hit_tile) {
+            (Some((t, _)), Some(tile_t)) if tile_t < t => (tile_t, None),
+            (Some((t, target)), _) => (t, Some(target)),
+            (None, Some(tile_t)) => (tile_t, None),
+            (None, None) => (1.0, None),
+        };
+        let pos = from + (to - from) * t;
+
+        let Some(projectile) = world.entity_mut(entity_id).and_then(|e| e.projectile.as_mut()) else {
+            continue;
+        };
+        projectile.lifetime_sec -= dt;
+        let landed = t < 1.0 || projectile.lifetime_sec <= 0.0;
+        if landed {
+            let projectile = projectile.clone();
+            land(world, entity_id, &projectile, from, pos, target);
+        } else {
+            move_projectile(world, entity_id, pos);
+        }
+    }
+}
+
+/// Moves the projectile to the new position, keeping track of the tile it is on
+fn move_projectile(world: &mut World, entity_id: DefaultKey, pos: Vec3) {
+    let Some(entity) = world.entity(entity_id) else {
+        return;
+    };
+    let old_tile_index = entity.tile_index();
+    let new_tile_index = pos.truncate().floor().as_ivec2();
+    if old_tile_index != new_tile_index {
+        if let Some(tile) = world.tiles.get_mut(old_tile_index) {
+            tile.entities.remove(&entity_id);
+        }
+        if let Some(tile) = world.tiles.get_mut(new_tile_index) {
+            tile.entities.insert(entity_id, ());
+        }
+    }
+    if let Some(entity) = world.entity_mut(entity_id) {
+        entity.transform.pos = pos;
+    }
+}
+
+/// Ends the flight of a projectile, damaging the entity it hit and leaving its item on the ground
+/// The impact is heard by zombies when the projectile was launched by the player
+fn land(world: &mut World, entity_id: DefaultKey, projectile: &Projectile, from: Vec3, pos: Vec3, target: Option<DefaultKey>) {
+    let owner = projectile.owner.unwrap_or(entity_id);
+    if let Some(target) = target {
+        world.events.push_back(Event::DamageEntity(ApplyDamageEvent {
+            entity_id: target,
+            other_entity_id: owner,
+            damage_amount: projectile.damage,
+        }));
+    }
+    world.events.push_back(Event::Noise(NoiseEvent {
+        entity_id: owner,
+        pos,
+        radius: IMPACT_NOISE_RADIUS,
+    }));
+
+    if let Some(item) = &projectile.drops {
+        // step back out of the wall or body which was hit, such that the item can be picked up
+        let back = (from - pos).normalize_or_zero() * projectile.radius;
+        let drop_pos = if world.is_tile_solid((pos + back).truncate().floor().as_ivec2()) { from } else { pos + back };
+        world.events.push_back(Event::Spawn(SpawnEvent {
+            pos: drop_pos,
+            prefab: "item".to_string(),
+            overrides: PrefabOverrides {
+                item: Some(Item {
+                    stack: ItemStack::new(item, 1),
+                    pickup_delay_sec: 0.0,
+                }),
+                ..Default::default()
+            },
+        }));
+    }
+    world.events.push_back(Event::Despawn(DespawnEvent { entity_id }));
+}
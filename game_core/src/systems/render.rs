@@ -1,15 +1,17 @@
 use std::collections::HashMap;
 
-use glam::{IVec2, Vec4};
+use glam::{IVec2, Vec2, Vec4};
 
-use crate::{Fade, Frame, Texture, TickEvent, World, systems::Ctx};
+use crate::{Fade, Frame, TickEvent, World, systems::Ctx};
 
 pub fn render_system(event:&TickEvent, ctx: &mut dyn Ctx) {
     let Some(player) = ctx.world_mut().player() else { return; };
     let player_index = player.tile_index();
-    let player_pos = player.pos;
+    let player_pos = player.transform.pos;
 
     let draw_radius = World::draw_radius();
+    // lights reaching into the drawn area
+    let lights = ctx.world_mut().lights(player_pos, draw_radius);
 
     let mut tiles:HashMap<IVec2, ()> = HashMap::new();
     let mut entities = Vec::new();
@@ -18,12 +20,17 @@ pub fn render_system(event:&TickEvent, ctx: &mut dyn Ctx) {
     for y in -draw_radius.ceil() as i32..=draw_radius.ceil() as i32 {
         for x in -draw_radius.ceil() as i32..=draw_radius.ceil() as i32 {
             let cell = player_index + glam::IVec2::new(x, y);
-            if let Some(_) = ctx.world_mut().tiles.get(cell) {
+            if let Some(tile) = ctx.world_mut().tiles.get(cell) {
+                let texture = tile.texture();
+                let sprite = tile.terrain.sprite();
                 let origin = glam::Vec3::new(cell.x as f32 + 0.5, cell.y as f32 + 0.5, 0.0);
-                let v = origin - player_pos;
-                let c = World::light(v.length());
-                let color = Vec4::new(c, c, c, c);
-                ctx.draw_tile(origin, Texture::Grass, Frame::Default, color);
+                let c = ctx.world_mut().light(&lights, origin);
+                let color = c.extend(c.max_element());
+                ctx.draw_tile(origin, texture, Frame::Default, color);
+                if let Some(sprite) = sprite {
+                    // upright parts of the terrain, such as walls
+                    ctx.draw_sprite(origin, sprite, Frame::Default, color.truncate().extend(1.0), Vec2::new(1.0, 1.5));
+                }
                 tiles.insert(cell, ());
             }
         }
@@ -31,6 +38,9 @@ pub fn render_system(event:&TickEvent, ctx: &mut dyn Ctx) {
 
     // collect entities to draw based upon their tile position
     for (id, entity) in ctx.world_mut().entities.iter() {
+        if entity.sprite.is_none() {
+            continue;
+        }
         let tile_index = entity.tile_index();
         if tiles.contains_key(&tile_index) {
             entities.push(id);
@@ -39,23 +49,24 @@ pub fn render_system(event:&TickEvent, ctx: &mut dyn Ctx) {
 
     // draw entities
     for e in entities.iter() {
-        if let Some(e) = ctx.world_mut().entities.get(*e) {
-            let origin = e.pos;
-            let v = origin - player_pos;
-            let d = v.length();
-            let c = World::light(d);
-            let color = e.tint * c;
-            let color = color.truncate().extend(1.0);
-            let sprite_size = e.sprite_size;
-            let texture = e.texture;
-            let floating_text = e.floating_text.clone();
-            let frame = e.frame;
+        let world = ctx.world_mut();
+        if let Some(e) = world.entities.get(*e) {
+            let Some(sprite) = &e.sprite else {
+                continue;
+            };
+            let origin = e.transform.pos;
+            let c = world.light(&lights, origin);
+            let color = (sprite.color().truncate() * c).extend(1.0);
+            let sprite_size = sprite.size;
+            let texture = sprite.texture;
+            let floating_text = sprite.floating_text.clone();
+            let frame = sprite.frame;
             ctx.draw_sprite(origin, texture, frame, color, sprite_size);
             
             // Draw floating text if present
             if let Some(text) = floating_text {
                 let text_pos = origin + glam::Vec3::new(0.0, 0.0, sprite_size.y * 1.0);
-                ctx.draw_text(text_pos, text, Vec4::new(c, c, c, 1.0));
+                ctx.draw_text(text_pos, text, c.extend(1.0));
             }
         }
     }
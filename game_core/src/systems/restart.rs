@@ -3,8 +3,5 @@ use super::Ctx;
 
 pub fn restart_system(_: &RestartEvent, ctx: &mut dyn Ctx) {
     ctx.world_mut().clear();
-    ctx.push_event(Event::Spawn(crate::event::SpawnEvent {
-        pos: glam::Vec3::default(),
-        variant: crate::EntityVariant::Player,
-    }));
+    ctx.push_event(Event::Spawn(crate::event::SpawnEvent::new(glam::Vec3::default(), "player")));
 }
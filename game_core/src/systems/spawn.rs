@@ -1,69 +1,73 @@
-use crate::{Entity, EntityVariant, Health, Timer};
+use crate::{Entity, EntityVariant, Health, Transform};
 use super::Ctx;
-use glam::Vec4;
 
+/// Spawns an entity from the prefab referred to by the event
+/// Unknown prefabs are ignored
 pub fn spawn_system(spawn_event: &crate::event::SpawnEvent, ctx: &mut dyn Ctx) {
-    let id = ctx.world_mut().entities.insert(Entity {
-        pos: spawn_event.pos,
-        variant: spawn_event.variant,
-        move_dir: Default::default(),
-        texture: Default::default(),
-        facing: 0.0,
-        solid: true,
-        radius: 0.4,
-        sprite_size: glam::Vec2::new(1.0, 1.0),
-        ability_activates_at_sec: 0.5,
-        ability_timer_sec: 0.0,
-        ability_timer_total_sec: 1.0,
-        move_sinus: 0.0,
-        move_distance_total: 0.0,
-        max_speed: 0.0,
-        floating_text: None,
-        frame: Default::default(),
-        move_sinus_speed: 1.0,
-        health: Health::indistructible(),
-        tint: Vec4::ONE,
-        flash_timer: Timer::new(0.25, true),
-    });
+    let Some(prefab) = ctx.world_mut().prefabs.get(&spawn_event.prefab).cloned() else {
+        return;
+    };
+    let overrides = &spawn_event.overrides;
 
-    match spawn_event.variant {
-        EntityVariant::Player => {
-            // set player entity id
-            ctx.world_mut().player = id;
-            let e = ctx.world_mut().entity_mut(id).unwrap();
-            e.ability_timer_total_sec = 0.5;
-            e.ability_activates_at_sec = 0.4;
-            e.max_speed = 2.5;
-            e.move_sinus_speed = 2.5;
-            e.health = Health {
-                current: 100.0,
-                max: 100.0,
-                can_receive_damage: true,
-            };
-            e.health = Health::indistructible();
-        },
-        EntityVariant::Tree => {
-            let w = ctx.rand_f32_range(1.0, 1.3);
-            let h = ctx.rand_f32_range(1.5, 2.5);
-            let e = ctx.world_mut().entity_mut(id).unwrap();
-            e.radius = 0.1;
-            e.texture = crate::Texture::Tree1;
-            e.sprite_size = glam::Vec2::new(w, h);
-            e.floating_text = Some("Tree".to_string());
-        },
-        EntityVariant::Zombie => {
-            let e = ctx.world_mut().entity_mut(id).unwrap();
-            e.texture = crate::Texture::Zombie1;
-            e.sprite_size = glam::Vec2::new(0.5, 1.0);
-            e.max_speed = 0.5;
-            e.move_sinus_speed = 20.0;
-            e.floating_text = Some("Zombie".to_string());
-            e.health = Health {
-                current: 30.0,
-                max: 30.0,
-                can_receive_damage: true,
+    let mut sprite = prefab.sprite;
+    if let Some(sprite) = &mut sprite {
+        match (overrides.sprite_size, prefab.sprite_size_max) {
+            (Some(size), _) => sprite.size = size,
+            (None, Some(max)) => {
+                let min = sprite.size;
+                let w = ctx.rand_f32_range(min.x, max.x);
+                let h = ctx.rand_f32_range(min.y, max.y);
+                sprite.size = glam::Vec2::new(w, h);
+            }
+            (None, None) => {}
+        }
+        if let Some(floating_text) = &overrides.floating_text {
+            sprite.floating_text = Some(floating_text.clone());
+        }
+    }
+
+    let mut mover = prefab.mover;
+    if let (Some(mover), Some(max_speed)) = (&mut mover, overrides.max_speed) {
+        mover.max_speed = max_speed;
+    }
+
+    let health = overrides.health.map(Health::new).or(prefab.health);
+
+    let item = overrides.item.clone().or(prefab.item);
+    if let (Some(sprite), Some(item)) = (&mut sprite, &item) {
+        // item entities look like the item they hold
+        if let Some(def) = ctx.world_mut().items.get(&item.stack.item) {
+            sprite.texture = def.texture;
+            if overrides.floating_text.is_none() {
+                sprite.floating_text = Some(def.label(item.stack.count));
             }
         }
-        EntityVariant::Unknown => {}
+    }
+
+    let id = ctx.world_mut().entities.insert(Entity {
+        variant: prefab.variant,
+        transform: Transform {
+            pos: spawn_event.pos,
+            facing: 0.0,
+        },
+        body: prefab.body,
+        sprite,
+        health,
+        ability: prefab.ability,
+        mover,
+        ai: prefab.ai,
+        perception: prefab.perception,
+        inventory: prefab.inventory,
+        item,
+        fellable: prefab.fellable,
+        crafting: None,
+        light_source: prefab.light_source,
+        survival: prefab.survival,
+        projectile: overrides.projectile.clone().or(prefab.projectile),
+    });
+
+    if matches!(prefab.variant, EntityVariant::Player) {
+        // set player entity id
+        ctx.world_mut().player = id;
     }
 }
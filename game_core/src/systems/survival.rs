@@ -0,0 +1,129 @@
+use glam::IVec2;
+
+use crate::{ApplyDamageEvent, ConsumeEvent, EntityVariant, Survival, Terrain, TickEvent, event::Event};
+use super::Ctx;
+
+/// Distance to a campfire within which entities warm up
+const CAMPFIRE_WARMTH_DISTANCE: f32 = 2.5;
+
+/// Warmth restored per second next to a campfire
+const CAMPFIRE_WARMTH_PER_SEC: f32 = 8.0;
+
+/// Warmth restored per second at noon
+const SUN_WARMTH_PER_SEC: f32 = 0.5;
+
+/// Water restored per second while standing next to water
+const DRINK_PER_SEC: f32 = 10.0;
+
+/// Stamina has to recover to this fraction of the maximum to no longer be exhausted
+const EXHAUSTION_RECOVERY: f32 = 0.3;
+
+/// Health lost for every depleted stat, once every interval
+const DRAIN_DAMAGE: f32 = 2.0;
+const DRAIN_INTERVAL_SEC: f32 = 2.0;
+
+/// Survival stats of living entities run out over time and are restored by their surroundings
+/// - Food and water are used up, water is restored while standing next to water
+/// - Warmth is lost at night and restored by the sun and campfires
+/// - Stamina recovers shortly after it was last used, unless food, water or warmth have run out
+/// - Every depleted stat drains health
+pub fn survival_system(event: &TickEvent, ctx: &mut dyn Ctx) {
+    let dt = event.dt;
+    let world = ctx.world_mut();
+    let daylight = world.clock.daylight();
+    let mut entities = Vec::new();
+    world.query_ids::<Survival>(&mut entities);
+    let mut close_entities = Vec::new();
+    for entity_id in entities {
+        let Some(entity) = world.entity(entity_id) else {
+            continue;
+        };
+        if !entity.is_alive() {
+            continue;
+        }
+        let pos = entity.transform.pos;
+        let tile_index = entity.tile_index();
+
+        close_entities.clear();
+        world.get_entities(tile_index, CAMPFIRE_WARMTH_DISTANCE, &mut close_entities);
+        let near_campfire = close_entities.iter().any(|id| {
+            world
+                .entity(*id)
+                .is_some_and(|e| e.variant == EntityVariant::Campfire && e.transform.pos.distance(pos) <= CAMPFIRE_WARMTH_DISTANCE)
+        });
+        let near_water = (-1..=1)
+            .flat_map(|y| (-1..=1).map(move |x| tile_index + IVec2::new(x, y)))
+            .any(|index| world.tiles.get(index).is_some_and(|tile| tile.terrain == Terrain::Water));
+
+        let Some(survival) = world.entity_mut(entity_id).and_then(|e| e.survival.as_mut()) else {
+            continue;
+        };
+        let warmth = if near_campfire {
+            CAMPFIRE_WARMTH_PER_SEC
+        } else {
+            SUN_WARMTH_PER_SEC * daylight - survival.warmth_loss_per_sec * (1.0 - daylight)
+        };
+        let water = if near_water { DRINK_PER_SEC } else { -survival.water_loss_per_sec };
+        survival.restore(-survival.food_loss_per_sec * dt, water * dt, warmth * dt);
+
+        let depleted = survival.depleted();
+        survival.exertion_timer_sec += dt;
+        if depleted == 0 && survival.exertion_timer_sec >= survival.stamina_regen_delay_sec {
+            survival.stamina = (survival.stamina + survival.stamina_regen_per_sec * dt).min(survival.max);
+        }
+        if survival.stamina <= 0.0 {
+            survival.exhausted = true;
+        } else if survival.stamina >= survival.max * EXHAUSTION_RECOVERY {
+            survival.exhausted = false;
+        }
+
+        if depleted == 0 {
+            survival.drain_timer_sec = 0.0;
+            continue;
+        }
+        survival.drain_timer_sec += dt;
+        if survival.drain_timer_sec >= DRAIN_INTERVAL_SEC {
+            survival.drain_timer_sec -= DRAIN_INTERVAL_SEC;
+            world.events.push_back(Event::DamageEntity(ApplyDamageEvent {
+                entity_id,
+                other_entity_id: entity_id,
+                damage_amount: DRAIN_DAMAGE * depleted as f32,
+            }));
+        }
+    }
+}
+
+/// Uses up an item of the inventory slot, restoring the survival stats and health of the entity
+/// Ignored if the item cannot be consumed
+pub fn consume_system(event: &ConsumeEvent, ctx: &mut dyn Ctx) {
+    let world = ctx.world_mut();
+    let Some(entity) = world.entity(event.entity_id) else {
+        return;
+    };
+    if !entity.is_alive() {
+        return;
+    }
+    let Some(stack) = entity
+        .inventory
+        .as_ref()
+        .and_then(|inventory| inventory.slots.get(event.slot)?.as_ref())
+    else {
+        return;
+    };
+    let Some(consumable) = world.items.get(&stack.item).and_then(|def| def.consumable.clone()) else {
+        return;
+    };
+
+    let Some(entity) = world.entity_mut(event.entity_id) else {
+        return;
+    };
+    if !entity.inventory.as_mut().is_some_and(|inventory| inventory.remove_from_slot(event.slot, 1)) {
+        return;
+    }
+    if let Some(survival) = &mut entity.survival {
+        survival.restore(consumable.food, consumable.water, consumable.warmth);
+    }
+    if let Some(health) = &mut entity.health {
+        health.heal(consumable.health);
+    }
+}
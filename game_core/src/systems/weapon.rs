@@ -0,0 +1,38 @@
+use crate::SwitchWeaponEvent;
+use super::Ctx;
+
+/// Wields the next weapon item carried by the entity, going back to the weapon of its ability after the last one
+/// Ignored while the ability is in progress
+pub fn switch_weapon_system(event: &SwitchWeaponEvent, ctx: &mut dyn Ctx) {
+    let world = ctx.world_mut();
+    let items = world.wieldable_items(event.entity_id);
+    let Some(entity) = world.entity_mut(event.entity_id) else {
+        return;
+    };
+    if !entity.is_alive() {
+        return;
+    }
+    let Some(ability) = &mut entity.ability else {
+        return;
+    };
+    if ability.is_in_progress() {
+        return;
+    }
+    // an item which is no longer carried counts as wielding the weapon of the ability
+    let current = ability
+        .wielded_item
+        .as_ref()
+        .and_then(|item| items.iter().position(|i| i == item));
+    ability.wielded_item = match current {
+        Some(index) => items.get(index + 1).cloned(),
+        None => items.first().cloned(),
+    };
+
+    // the next swing already uses the timings of the new weapon
+    let Some(weapon) = world.weapon(event.entity_id).cloned() else {
+        return;
+    };
+    if let Some(ability) = world.entity_mut(event.entity_id).and_then(|e| e.ability.as_mut()) {
+        ability.equip(&weapon);
+    }
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Texture;
+
+/// Kind of ground of a tile, affecting how entities move over it
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Terrain {
+    #[default]
+    Grass,
+    DirtPath,
+    Mud,
+    Water,
+    Rock,
+    Snow,
+    /// Wall of a building, drawn as an upright sprite
+    Wall,
+    /// Wooden floor within a building
+    Floor,
+}
+
+impl Terrain {
+    /// Texture of the ground
+    /// Grass is drawn using the grass of the tile's biome instead, see [`crate::Tile::texture`]
+    pub fn texture(&self) -> Texture {
+        match self {
+            Terrain::Grass => Texture::Grass,
+            Terrain::DirtPath => Texture::Dirt,
+            Terrain::Mud => Texture::Mud,
+            Terrain::Water => Texture::Water,
+            Terrain::Rock => Texture::Rock,
+            Terrain::Snow => Texture::Snow,
+            Terrain::Wall | Terrain::Floor => Texture::Planks,
+        }
+    }
+
+    /// Texture of the upright sprite drawn on top of the ground, if any
+    pub fn sprite(&self) -> Option<Texture> {
+        match self {
+            Terrain::Wall => Some(Texture::Wall),
+            _ => None,
+        }
+    }
+
+    /// Multiplier of the speed of entities moving over the terrain
+    pub fn speed_multiplier(&self) -> f32 {
+        match self {
+            Terrain::Grass => 1.0,
+            Terrain::DirtPath => 1.2,
+            Terrain::Mud => 0.5,
+            Terrain::Water => 1.0,
+            Terrain::Rock => 1.0,
+            Terrain::Snow => 0.8,
+            Terrain::Wall => 1.0,
+            Terrain::Floor => 1.0,
+        }
+    }
+
+    /// Multiplier of the distance at which footsteps on the terrain can be heard
+    pub fn footstep_noise(&self) -> f32 {
+        match self {
+            Terrain::Grass => 1.0,
+            Terrain::DirtPath => 0.7,
+            Terrain::Mud => 1.3,
+            Terrain::Water => 1.0,
+            Terrain::Rock => 1.0,
+            Terrain::Snow => 1.5,
+            Terrain::Wall => 1.0,
+            Terrain::Floor => 1.2,
+        }
+    }
+
+    /// Whether the terrain is impassable
+    pub fn is_solid(&self) -> bool {
+        matches!(self, Terrain::Water | Terrain::Rock | Terrain::Wall)
+    }
+
+    /// Whether the terrain blocks the line of sight
+    pub fn is_opaque(&self) -> bool {
+        matches!(self, Terrain::Rock | Terrain::Wall)
+    }
+}
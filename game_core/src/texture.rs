@@ -1,9 +1,35 @@
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Texture {
     None,
     Tree1,
     Zombie1,
-    Grass
+    Grass,
+    SwampGrass,
+    Ash,
+    MeadowGrass,
+    Water,
+    Dirt,
+    Mud,
+    Rock,
+    Snow,
+    Planks,
+    Wall,
+    Crate,
+    Campfire,
+    Wood,
+    Stone,
+    Berries,
+    Bandage,
+    Cloth,
+    Torch,
+    Spear,
+    Barricade,
+    Knife,
+    WoodsmanAxe,
+    Bow,
+    Arrow,
 }
 
 impl Default for Texture {
@@ -1,12 +1,38 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
+use serde::{Deserialize, Serialize};
 use slotmap::DefaultKey;
 
-#[derive(Clone, Default)]
+use crate::{Biome, Terrain, Texture};
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Tile {
-    /// Whether this tile is solid (i.e., impassable)
-    pub solid: bool,
+    /// Kind of ground of this tile
+    pub terrain: Terrain,
+
+    /// Biome this tile belongs to
+    pub biome: Biome,
 
     /// List of entity IDs currently occupying this tile
-    pub entities: HashMap<DefaultKey, ()>,
+    /// Ordered to keep iteration deterministic
+    /// Not saved, since the mapping is rebuilt every tick
+    #[serde(skip)]
+    pub entities: BTreeMap<DefaultKey, ()>,
+}
+
+impl Tile {
+    /// Whether this tile is solid (i.e., impassable), which is decided by its terrain
+    /// Placed obstacles, such as walls, are solid entities instead, since the tiles are generated again when a chunk is loaded
+    pub fn is_solid(&self) -> bool {
+        self.terrain.is_solid()
+    }
+
+    /// Texture of the ground, grass is drawn using the grass of the biome
+    pub fn texture(&self) -> Texture {
+        match self.terrain {
+            Terrain::Grass => self.biome.texture(),
+            terrain => terrain.texture(),
+        }
+    }
 }
@@ -1,4 +1,6 @@
-#[derive(Default, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub struct Timer {
     duration_sec: f32,
     elapsed: f32,
@@ -0,0 +1,90 @@
+use std::{collections::BTreeMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AssetError, asset};
+
+/// Weapons compiled into the game, used when no weapons are loaded from disk
+const BUILTIN_WEAPONS: &str = include_str!("../../assets/weapons.ron");
+
+/// Describes how a weapon hits when its wielder uses their ability
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeaponDef {
+    /// Name shown to the player
+    pub name: String,
+
+    /// Damage dealt to every entity hit
+    pub damage: f32,
+
+    /// Distance from the wielder within which entities are hit
+    pub reach: f32,
+
+    /// Full angle of the cone in front of the wielder within which entities are hit, in radians
+    /// An arc of 0 hits along a single line
+    pub arc: f32,
+
+    /// Time in seconds between the start of two swings
+    pub cooldown_sec: f32,
+
+    /// Time in seconds from the start of a swing until it hits
+    pub wind_up_sec: f32,
+
+    /// Whether every entity within reach is hit, otherwise only the closest one
+    pub hits_multiple: bool,
+
+    /// Prefab launched towards the facing direction instead of hitting within reach, such as an arrow
+    pub projectile: Option<String>,
+
+    /// Item used up by every launch, nothing is launched once it runs out
+    pub ammo: Option<String>,
+}
+
+impl Default for WeaponDef {
+    fn default() -> Self {
+        WeaponDef {
+            name: String::new(),
+            damage: 15.0,
+            reach: 1.0,
+            arc: 0.0,
+            cooldown_sec: 1.0,
+            wind_up_sec: 0.5,
+            hits_multiple: false,
+            projectile: None,
+            ammo: None,
+        }
+    }
+}
+
+/// Registry of all weapons by id
+/// Ordered, such that weapons are listed in the same order every time
+#[derive(Clone)]
+pub struct WeaponRegistry {
+    pub weapons: BTreeMap<String, WeaponDef>,
+}
+
+impl Default for WeaponRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl WeaponRegistry {
+    /// The weapons compiled into the game
+    pub fn builtin() -> Self {
+        WeaponRegistry {
+            weapons: asset::parse_ron(BUILTIN_WEAPONS).expect("builtin weapons should be valid"),
+        }
+    }
+
+    /// Load weapons from a RON file mapping weapon ids to weapons
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AssetError> {
+        Ok(WeaponRegistry {
+            weapons: asset::load_ron(path)?,
+        })
+    }
+
+    pub fn get(&self, id: &str) -> Option<&WeaponDef> {
+        self.weapons.get(id)
+    }
+}
@@ -1,25 +1,58 @@
-use std::collections::VecDeque;
+use std::{collections::{BTreeMap, VecDeque}, hash::{Hash, Hasher}, path::Path};
 
-use glam::IVec2;
-use slotmap::DefaultKey;
+use glam::{IVec2, Vec3};
+use serde::{Deserialize, Serialize};
+use slotmap::{DefaultKey, Key};
 
-use crate::{Event, Timer, entity::Entity, tile::Tile};
+use crate::{AssetError, BehaviourRegistry, Clock, ItemRegistry, Light, LightSource, RecipeRegistry, StructureRegistry, Checksum, Chunk, Component, Event, chunk_index, chunk_tiles, PrefabRegistry, Rng, Terrain, Timer, WeaponDef, WeaponRegistry, entity::Entity, light, math, tile::Tile};
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub struct World {
     /// All entities in the game world
     pub entities: slotmap::SlotMap<slotmap::DefaultKey, Entity>,
     /// The tiles making up the game world
+    #[serde(with = "crate::save::tiles")]
     pub tiles: endlessgrid::Grid<Tile>,
+    /// All chunks which have been generated, loaded or not, by chunk index
+    /// Ordered to keep iteration and saves deterministic
+    pub chunks: BTreeMap<(i32, i32), Chunk>,
     /// The players entity id
     pub player: slotmap::DefaultKey,
     /// Unprocessed events
     pub events:VecDeque<Event>,
     pub fade_timer: Timer,
-    pub fade:Fade
+    pub fade:Fade,
+    /// Seed used to initialize the random number generator
+    pub seed: u64,
+    /// Random number generator, seeded from `seed` on every restart
+    pub rng: Rng,
+    /// Time of day and number of nights survived
+    #[serde(default)]
+    pub clock: Clock,
+    /// Prefabs which can be spawned, not saved since they are loaded from the assets
+    #[serde(skip)]
+    pub prefabs: PrefabRegistry,
+    /// Bot behaviours, not saved since they are loaded from the assets
+    #[serde(skip)]
+    pub behaviours: BehaviourRegistry,
+    /// Structures placed during generation, not saved since they are loaded from the assets
+    #[serde(skip)]
+    pub structures: StructureRegistry,
+    /// Items which can be carried, not saved since they are loaded from the assets
+    #[serde(skip)]
+    pub items: ItemRegistry,
+    /// Recipes which can be crafted, not saved since they are loaded from the assets
+    #[serde(skip)]
+    pub recipes: RecipeRegistry,
+    /// Weapons which can be wielded, not saved since they are loaded from the assets
+    #[serde(skip)]
+    pub weapons: WeaponRegistry,
 }
 
-#[derive(Clone)]
+/// Distance in front of an entity of the position it builds at
+const BUILD_DISTANCE: f32 = 1.0;
+
+#[derive(Clone, Serialize, Deserialize)]
 pub enum Fade {
     In,
     Out,
@@ -31,43 +64,93 @@ impl Default for Fade {
 }   
 
 impl World {
+    /// Create an empty world using the given seed
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: Rng::new(seed),
+            ..Default::default()
+        }
+    }
+
     pub fn start_fade(&mut self, fade: Fade, duration:f32) {
         self.fade = fade;
         self.fade_timer = Timer::new(duration, false);
     }
 
-    pub fn light(d:f32) -> f32 {
-        let max_distance = Self::view_radius();
-        
-        // Inverse square falloff with smooth cutoff
-        // This provides more realistic light attenuation
-        let normalized_d = d / max_distance;
-        
-        if normalized_d >= 1.0 {
-            return 0.0;
-        }
-        
-        // Inverse square law: intensity = 1 / (1 + k*d^2)
-        // k controls the falloff rate
-        let k = 16.0;
-        let attenuation = 1.0 / (1.0 + k * normalized_d * normalized_d);
-        
-        // Smooth cutoff near the edge to avoid hard boundaries
-        let edge_softness = 0.2;
-        if normalized_d > (1.0 - edge_softness) {
-            let edge_factor = (1.0 - normalized_d) / edge_softness;
-            attenuation * edge_factor
-        } else {
-            attenuation
-        }
+    /// Color of the light at a position, combining the natural light around the player and the given lights
+    /// The natural light is tinted by the time of day, lights keep their own color
+    pub fn light(&self, lights: &[Light], pos: Vec3) -> Vec3 {
+        let d = self.player().map(|player| player.transform.pos.distance(pos)).unwrap_or_default();
+        // daylight fades out towards the edge of the drawn area, like fog
+        let daylight = self.clock.daylight() * (1.0 - d / Self::draw_radius()).clamp(0.0, 1.0);
+        let natural = light::falloff(d, self.clock.light_radius()).max(daylight);
+        let color = self.clock.ambient_color().truncate() * natural
+            + lights.iter().map(|light| light.color_at(pos)).sum::<Vec3>();
+        color.min(Vec3::ONE)
+    }
+
+    /// Lights of all light sources reaching within the distance of the position
+    pub fn lights(&self, pos: Vec3, distance: f32) -> Vec<Light> {
+        self.query::<LightSource>()
+            .filter_map(|(entity_id, light_source)| {
+                let e = self.entity(entity_id)?;
+                let radius = light_source.current_radius();
+                if !e.is_alive() || radius <= 0.0 || e.transform.pos.distance(pos) > distance + radius {
+                    return None;
+                }
+                // lights flicker out of sync with each other
+                let phase = e.transform.pos.x * 12.9898 + e.transform.pos.y * 78.233;
+                let flicker = light::flicker(light_source.flicker_sec, phase);
+                Some(Light {
+                    pos: e.transform.pos,
+                    color: light_source.color,
+                    radius: radius * (1.0 - light_source.flicker * flicker),
+                })
+            })
+            .collect()
     }
 
     pub fn draw_radius() -> f32 {
         8.0
     }
 
-    pub fn view_radius() -> f32 {
-        6.0
+    /// Radius of the light around the player, the larger of the natural light and the player's torch
+    pub fn light_radius(&self) -> f32 {
+        let torch_radius = self
+            .player()
+            .and_then(|player| player.light_source.as_ref())
+            .map(|light_source| light_source.current_radius())
+            .unwrap_or_default();
+        self.clock.light_radius().max(torch_radius)
+    }
+
+    /// Weapon the entity fights with, which is the weapon of the wielded item while it is carried
+    /// and the weapon of the entity's ability otherwise
+    pub fn weapon(&self, entity_id: DefaultKey) -> Option<&WeaponDef> {
+        let e = self.entity(entity_id)?;
+        let ability = e.ability.as_ref()?;
+        let wielded = ability
+            .wielded_item
+            .as_ref()
+            .filter(|item| e.inventory.as_ref().is_some_and(|inventory| inventory.count(item) > 0))
+            .and_then(|item| self.items.get(item)?.weapon.as_ref());
+        self.weapons.get(wielded.unwrap_or(&ability.weapon))
+    }
+
+    /// Carried items of the entity which can be wielded as a weapon, in the order of the inventory
+    pub fn wieldable_items(&self, entity_id: DefaultKey) -> Vec<String> {
+        let mut items: Vec<String> = Vec::new();
+        let Some(inventory) = self.entity(entity_id).and_then(|e| e.inventory.as_ref()) else {
+            return items;
+        };
+        for stack in inventory.slots.iter().flatten() {
+            let is_weapon = self.items.get(&stack.item).is_some_and(|def| def.weapon.is_some());
+            if is_weapon && !items.contains(&stack.item) {
+                items.push(stack.item.clone());
+            }
+        }
+        items
     }
 
     /// Get all entity IDs in the world
@@ -77,6 +160,23 @@ impl World {
         }
     }
 
+    /// Get all entity IDs in the world which have the component `C`
+    pub fn query_ids<C: Component>(&self, entities:&mut Vec<DefaultKey>) {
+        for (entity_id, _) in self.query::<C>() {
+            entities.push(entity_id);
+        }
+    }
+
+    /// Iterate all entities which have the component `C`
+    pub fn query<C: Component>(&self) -> impl Iterator<Item = (DefaultKey, &C)> {
+        self.entities.iter().filter_map(|(entity_id, e)| Some((entity_id, e.get::<C>()?)))
+    }
+
+    /// Iterate all entities which have the component `C`, allowing the component to be changed
+    pub fn query_mut<C: Component>(&mut self) -> impl Iterator<Item = (DefaultKey, &mut C)> {
+        self.entities.iter_mut().filter_map(|(entity_id, e)| Some((entity_id, e.get_mut::<C>()?)))
+    }
+
     pub fn entity_mut(&mut self, entity_id:DefaultKey) -> Option<&mut Entity> {
         self.entities.get_mut(entity_id)
     }
@@ -86,9 +186,13 @@ impl World {
     }
 
     /// Clear the world of all entities and tiles
+    /// The random number generator is reseeded, such that the world is generated identically
     pub fn clear(&mut self) {
         self.entities.clear();
         self.tiles = Default::default();
+        self.chunks.clear();
+        self.rng = Rng::new(self.seed);
+        self.clock = Clock::default();
         self.start_fade(Fade::In, 1.0);
     }
 
@@ -102,6 +206,262 @@ impl World {
         self.entities.get(self.player)
     }
 
+    /// Load the registries from the RON files in the given directory, such as `assets`
+    /// Registries which fail to load are left unchanged, their errors are returned along with the name of the registry
+    pub fn load_registries(&mut self, dir: impl AsRef<Path>) -> Vec<(&'static str, AssetError)> {
+        let dir = dir.as_ref();
+        let mut errors = Vec::new();
+        match PrefabRegistry::load(dir.join("prefabs.ron")) {
+            Ok(prefabs) => self.prefabs = prefabs,
+            Err(err) => errors.push(("prefabs", err)),
+        }
+        match BehaviourRegistry::load(dir.join("behaviours.ron")) {
+            Ok(behaviours) => self.behaviours = behaviours,
+            Err(err) => errors.push(("behaviours", err)),
+        }
+        match StructureRegistry::load(dir.join("structures.ron")) {
+            Ok(structures) => self.structures = structures,
+            Err(err) => errors.push(("structures", err)),
+        }
+        match ItemRegistry::load(dir.join("items.ron")) {
+            Ok(items) => self.items = items,
+            Err(err) => errors.push(("items", err)),
+        }
+        match RecipeRegistry::load(dir.join("recipes.ron")) {
+            Ok(recipes) => self.recipes = recipes,
+            Err(err) => errors.push(("recipes", err)),
+        }
+        match WeaponRegistry::load(dir.join("weapons.ron")) {
+            Ok(weapons) => self.weapons = weapons,
+            Err(err) => errors.push(("weapons", err)),
+        }
+        errors
+    }
+
+    /// Compute a checksum of the registries, which are not saved but change how the world is simulated
+    /// Used to check that a replay uses the same prefabs, items, recipes and so on as the recording
+    pub fn registries_checksum(&self) -> u64 {
+        // hash maps are sorted by id first, such that the checksum does not depend on their order
+        let registries = (
+            self.prefabs.prefabs.iter().collect::<BTreeMap<_, _>>(),
+            self.behaviours.behaviours.iter().collect::<BTreeMap<_, _>>(),
+            &self.structures.structures,
+            self.items.items.iter().collect::<BTreeMap<_, _>>(),
+            &self.recipes.recipes,
+            &self.weapons.weapons,
+        );
+        let ron = ron::to_string(&registries).expect("registries should serialize");
+        let mut h = Checksum::default();
+        h.write(ron.as_bytes());
+        h.finish()
+    }
+
+    /// Compute a checksum of the simulation state of the world
+    /// Two worlds with the same checksum are considered identical, which is used to verify replays
+    pub fn checksum(&self) -> u64 {
+        let mut h = Checksum::default();
+        self.rng.hash(&mut h);
+        h.write_u32(self.clock.time_sec.to_bits());
+        h.write_usize(self.entities.len());
+        for ((x, y), chunk) in self.chunks.iter() {
+            h.write_i32(*x);
+            h.write_i32(*y);
+            h.write_u8(chunk.loaded as u8);
+            h.write_usize(chunk.entities.len());
+        }
+        for (entity_id, e) in self.entities.iter() {
+            h.write_u64(entity_id.data().as_ffi());
+            h.write_u8(e.variant as u8);
+            h.write_u8(e.frame() as u8);
+            let pos = e.transform.pos;
+            for v in [pos.x, pos.y, pos.z, e.transform.facing] {
+                h.write_u32(v.to_bits());
+            }
+            if let Some(mover) = &e.mover {
+                for v in [mover.move_dir.x, mover.move_dir.y, mover.move_dir.z] {
+                    h.write_u32(v.to_bits());
+                }
+            }
+            if let Some(health) = &e.health {
+                h.write_u32(health.current.to_bits());
+            }
+            if let Some(ability) = &e.ability {
+                h.write_u32(ability.timer_sec.to_bits());
+            }
+            if let Some(inventory) = &e.inventory {
+                for stack in inventory.slots.iter().flatten() {
+                    stack.item.hash(&mut h);
+                    h.write_u32(stack.count);
+                }
+            }
+            if let Some(item) = &e.item {
+                item.stack.item.hash(&mut h);
+                h.write_u32(item.stack.count);
+            }
+            if let Some(crafting) = &e.crafting {
+                crafting.recipe.hash(&mut h);
+                h.write_u32(crafting.timer_sec.to_bits());
+            }
+            if let Some(light_source) = &e.light_source {
+                h.write_u32(light_source.fuel_sec.to_bits());
+            }
+            if let Some(survival) = &e.survival {
+                for v in [survival.food, survival.water, survival.warmth, survival.stamina] {
+                    h.write_u32(v.to_bits());
+                }
+            }
+        }
+        h.finish()
+    }
+
+    /// Unload the given chunks, removing their tiles and moving their entities into the chunks
+    /// Entities which have left the loaded chunks are moved into the chunk they are in as well
+    pub fn unload_chunks(&mut self, chunk_indices: &[IVec2]) {
+        for index in chunk_indices {
+            if let Some(chunk) = self.chunks.get_mut(&(*index).into()) {
+                chunk.loaded = false;
+            }
+        }
+
+        let mut unloaded_entities = Vec::new();
+        for (entity_id, e) in self.entities.iter() {
+            let index = chunk_index(e.tile_index());
+            if entity_id != self.player && !self.chunks.get(&index.into()).is_some_and(|chunk| chunk.loaded) {
+                unloaded_entities.push((entity_id, index));
+            }
+        }
+        for (entity_id, index) in unloaded_entities {
+            if let Some(e) = self.entities.remove(entity_id) {
+                let chunk = self.chunks.entry(index.into()).or_default();
+                chunk.entities.push(e);
+                chunk.entity_ids.push(entity_id);
+            }
+        }
+
+        // the grid cannot remove tiles, so it is rebuilt from the tiles of the loaded chunks
+        let old_tiles = std::mem::take(&mut self.tiles);
+        for (index, chunk) in self.chunks.iter() {
+            if !chunk.loaded {
+                continue;
+            }
+            for tile_index in chunk_tiles((*index).into()) {
+                if let Some(tile) = old_tiles.get(tile_index) {
+                    self.tiles.insert(tile_index, tile.clone());
+                }
+            }
+        }
+    }
+
+    /// Move the entities of an unloaded chunk back into the world
+    /// The entities get new ids, ids referring to their old ones, such as the owner of a projectile, are changed to the new ids
+    pub fn load_chunk_entities(&mut self, chunk_index: IVec2) {
+        let Some(chunk) = self.chunks.get_mut(&chunk_index.into()) else {
+            return;
+        };
+        let entities = std::mem::take(&mut chunk.entities);
+        let old_ids = std::mem::take(&mut chunk.entity_ids);
+        let mut new_ids = BTreeMap::new();
+        for (old_id, e) in old_ids.into_iter().zip(entities) {
+            new_ids.insert(old_id, self.entities.insert(e));
+        }
+        if new_ids.is_empty() {
+            return;
+        }
+        // pending events are not changed, they only refer to entities until they are processed within the tick
+        let loaded = self.entities.values_mut();
+        let unloaded = self.chunks.values_mut().flat_map(|chunk| chunk.entities.iter_mut());
+        for e in loaded.chain(unloaded) {
+            if let Some(projectile) = &mut e.projectile {
+                if let Some(owner) = projectile.owner.and_then(|owner| new_ids.get(&owner)) {
+                    projectile.owner = Some(*owner);
+                }
+            }
+        }
+    }
+
+    /// Returns true if the tile at the given index is solid
+    /// Tiles which have not been generated yet are not solid
+    pub fn is_tile_solid(&self, tile_index:IVec2) -> bool {
+        self.tiles.get(tile_index).is_some_and(|tile| tile.is_solid())
+    }
+
+    /// Returns the terrain of the tile at the given index
+    /// Tiles which have not been generated yet are grass
+    pub fn terrain(&self, tile_index:IVec2) -> Terrain {
+        self.tiles.get(tile_index).map(|tile| tile.terrain).unwrap_or_default()
+    }
+
+    /// Returns true if nothing blocks the line of sight between two positions
+    /// Opaque tiles (e.g. rocks) and solid entities which cannot move (e.g. trees) block the line of sight
+    pub fn line_of_sight(&self, from:Vec3, to:Vec3) -> bool {
+        let from = from.truncate();
+        let to = to.truncate();
+        let steps = (from.distance(to) / 0.25).ceil().max(1.0) as i32;
+        let mut last_cell = None;
+        for i in 0..=steps {
+            let p = from.lerp(to, i as f32 / steps as f32);
+            let cell = p.floor().as_ivec2();
+            if last_cell == Some(cell) {
+                continue;
+            }
+            last_cell = Some(cell);
+            let Some(tile) = self.tiles.get(cell) else {
+                continue;
+            };
+            if tile.terrain.is_opaque() {
+                return false;
+            }
+            for entity_id in tile.entities.keys() {
+                let Some(e) = self.entity(*entity_id) else {
+                    continue;
+                };
+                let Some(body) = &e.body else {
+                    continue;
+                };
+                if e.mover.is_some() || !body.solid {
+                    continue;
+                }
+                if math::distance_to_segment(e.transform.pos.truncate(), from, to) < body.radius {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Returns the tile in front of the entity, onto which it builds
+    pub fn build_target(&self, entity_id:DefaultKey) -> Option<IVec2> {
+        let e = self.entity(entity_id)?;
+        let facing = e.transform.facing;
+        let pos = e.transform.pos + Vec3::new(facing.cos(), facing.sin(), 0.0) * BUILD_DISTANCE;
+        Some(pos.truncate().floor().as_ivec2())
+    }
+
+    /// Returns true if a building can be placed onto the tile
+    /// The tile must be generated and not solid, and no solid entity may overlap it
+    pub fn can_build_at(&self, tile_index:IVec2) -> bool {
+        let Some(tile) = self.tiles.get(tile_index) else {
+            return false;
+        };
+        if tile.is_solid() {
+            return false;
+        }
+        let min = tile_index.as_vec2();
+        let max = min + glam::Vec2::ONE;
+        let mut close_entities = Vec::new();
+        self.get_entities(tile_index, 1.0, &mut close_entities);
+        !close_entities.iter().any(|entity_id| {
+            let Some(e) = self.entity(*entity_id) else {
+                return false;
+            };
+            let Some(body) = e.body.as_ref().filter(|body| body.solid) else {
+                return false;
+            };
+            let pos = e.transform.pos.truncate();
+            pos.clamp(min, max).distance(pos) < body.radius
+        })
+    }
+
     /// Get all entities within a certain radius of a tile position
     pub fn get_entities(&self, tile_pos:IVec2, radius:f32, entities:&mut Vec<DefaultKey>) {
         let s = radius.ceil() as i32;
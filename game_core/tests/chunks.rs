@@ -0,0 +1,37 @@
+mod common;
+
+use game_core::{EntityVariant, HeadlessCtx, Projectile};
+use glam::Vec3;
+
+/// Moves the player by the offset and steps once, such that the chunks around the player are loaded and unloaded
+fn teleport_player(ctx: &mut HeadlessCtx, offset: Vec3) {
+    ctx.world.player_mut().unwrap().transform.pos += offset;
+    let input = common::idle(ctx);
+    ctx.step(1.0 / 60.0, input);
+}
+
+#[test]
+fn ids_referring_to_unloaded_entities_are_updated_when_loaded_again() {
+    let mut ctx = HeadlessCtx::new(9);
+    common::clear_around_player(&mut ctx);
+    let crate_id = common::spawn_near_player(&mut ctx, Vec3::new(3.0, 0.0, 0.0), "loot_crate");
+    let arrow_id = common::spawn_near_player(&mut ctx, Vec3::new(5.0, 0.0, 0.0), "arrow");
+    // an arrow lying still, as if it was launched by the crate
+    let projectile = ctx.world.entity_mut(arrow_id).unwrap().projectile.as_mut().unwrap();
+    projectile.owner = Some(crate_id);
+    projectile.velocity = Vec3::ZERO;
+    projectile.lifetime_sec = 100.0;
+
+    teleport_player(&mut ctx, Vec3::new(100.0, 0.0, 0.0));
+    assert!(ctx.world.entity(crate_id).is_none());
+    assert!(ctx.world.entity(arrow_id).is_none());
+    let unloaded: Vec<_> = ctx.world.chunks.values().filter(|chunk| !chunk.loaded).collect();
+    assert!(unloaded.iter().all(|chunk| chunk.entities.len() == chunk.entity_ids.len()));
+    assert!(unloaded.iter().any(|chunk| chunk.entity_ids.contains(&crate_id)));
+
+    teleport_player(&mut ctx, Vec3::new(-100.0, 0.0, 0.0));
+    let (_, projectile) = ctx.world.query::<Projectile>().next().expect("arrow should be loaded again");
+    let owner = ctx.world.entity(projectile.owner.unwrap()).expect("owner should refer to the loaded crate");
+    assert_eq!(owner.variant, EntityVariant::Crate);
+    assert!(ctx.world.chunks.values().all(|chunk| chunk.loaded || chunk.entities.len() == chunk.entity_ids.len()));
+}
@@ -0,0 +1,27 @@
+mod common;
+
+use game_core::HeadlessCtx;
+use glam::{IVec2, Vec3};
+
+fn walk_east(ctx: &mut HeadlessCtx, ticks: usize) {
+    for _ in 0..ticks {
+        let input = game_core::PlayerInputEvent {
+            move_dir: Vec3::X,
+            ..common::idle(ctx)
+        };
+        ctx.step(1.0 / 60.0, input);
+    }
+}
+
+#[test]
+fn solid_terrain_blocks_movement() {
+    let mut ctx = HeadlessCtx::new(2);
+    common::clear_around_player(&mut ctx);
+    let start = ctx.world.player().unwrap().tile_index();
+    ctx.world.tiles.get_mut(start + IVec2::new(2, 0)).unwrap().terrain = game_core::Terrain::Rock;
+
+    walk_east(&mut ctx, 120);
+    let player = ctx.world.player().unwrap();
+    assert!(player.transform.pos.x < (start.x + 2) as f32);
+}
+
@@ -0,0 +1,73 @@
+#![allow(dead_code)]
+
+use game_core::{HeadlessCtx, PlayerInputEvent};
+use glam::Vec3;
+
+/// Input of a player who does nothing
+pub fn idle(ctx: &HeadlessCtx) -> PlayerInputEvent {
+    PlayerInputEvent {
+        player_id: ctx.world.player,
+        move_dir: Vec3::ZERO,
+        facing: 0.0,
+        use_ability: false,
+        drop_slot: None,
+        craft: None,
+        build_slot: None,
+        refuel: false,
+        consume_slot: None,
+        sprint: false,
+        dodge: false,
+        switch_weapon: false,
+    }
+}
+
+/// Input of the `i`th tick of a player walking around in circles and swinging their axe now and then
+pub fn wander(ctx: &HeadlessCtx, i: usize) -> PlayerInputEvent {
+    let angle = i as f32 * 0.01;
+    PlayerInputEvent {
+        move_dir: Vec3::new(angle.cos(), angle.sin(), 0.0),
+        facing: angle,
+        use_ability: i.is_multiple_of(45),
+        ..idle(ctx)
+    }
+}
+
+/// Steps the context `ticks` times at 60 ticks per second using the wandering input
+pub fn run(ctx: &mut HeadlessCtx, ticks: usize) {
+    for i in 0..ticks {
+        let input = wander(ctx, i);
+        ctx.step(1.0 / 60.0, input);
+    }
+}
+
+/// Removes every entity but the player and turns the ground around the player into open grass,
+/// such that tests can place the entities they need
+pub fn clear_around_player(ctx: &mut HeadlessCtx) {
+    // the first tick generates the tiles around the player
+    let input = idle(ctx);
+    ctx.step(1.0 / 60.0, input);
+    let player = ctx.world.player;
+    let others: Vec<_> = ctx.world.entities.keys().filter(|id| *id != player).collect();
+    for id in others {
+        ctx.world.entities.remove(id);
+    }
+    let center = ctx.world.player().unwrap().tile_index();
+    for y in -12..=12 {
+        for x in -12..=12 {
+            if let Some(tile) = ctx.world.tiles.get_mut(center + glam::IVec2::new(x, y)) {
+                tile.terrain = game_core::Terrain::Grass;
+                tile.entities.clear();
+            }
+        }
+    }
+}
+
+/// Spawns the prefab at an offset from the player and returns the id of the spawned entity
+pub fn spawn_near_player(ctx: &mut HeadlessCtx, offset: Vec3, prefab: &str) -> slotmap::DefaultKey {
+    let pos = ctx.world.player().unwrap().transform.pos + offset;
+    let before: Vec<_> = ctx.world.entities.keys().collect();
+    ctx.world.events.push_back(game_core::Event::Spawn(game_core::SpawnEvent::new(pos, prefab)));
+    let input = idle(ctx);
+    ctx.step(1.0 / 60.0, input);
+    ctx.world.entities.keys().find(|id| !before.contains(id)).expect("prefab should spawn")
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use game_core::{DrawCall, HeadlessCtx};
+
+#[test]
+fn same_seed_and_input_give_the_same_world() {
+    let mut a = HeadlessCtx::new(42);
+    let mut b = HeadlessCtx::new(42);
+    assert_eq!(a.world.checksum(), b.world.checksum());
+
+    common::run(&mut a, 600);
+    common::run(&mut b, 600);
+    assert_eq!(a.world.checksum(), b.world.checksum());
+}
+
+#[test]
+fn different_seeds_give_different_worlds() {
+    let mut a = HeadlessCtx::new(1);
+    let mut b = HeadlessCtx::new(2);
+    common::run(&mut a, 60);
+    common::run(&mut b, 60);
+    assert_ne!(a.world.checksum(), b.world.checksum());
+}
+
+#[test]
+fn step_records_draw_calls_of_the_frame() {
+    let mut ctx = HeadlessCtx::new(7);
+    assert!(ctx.frame_log.is_empty());
+
+    let input = common::idle(&ctx);
+    ctx.step(1.0 / 60.0, input);
+    let tiles = ctx.frame_log.iter().filter(|call| matches!(call, DrawCall::Tile { .. })).count();
+    let sprites = ctx.frame_log.iter().filter(|call| matches!(call, DrawCall::Sprite { .. })).count();
+    assert!(tiles > 0);
+    assert!(sprites > 0);
+
+    // the log only holds the draw calls of the last step, standing still draws the same tiles again
+    let input = common::idle(&ctx);
+    ctx.step(1.0 / 60.0, input);
+    assert_eq!(ctx.frame_log.iter().filter(|call| matches!(call, DrawCall::Tile { .. })).count(), tiles);
+}
+
+#[test]
+fn runs_many_ticks_without_panicking() {
+    let mut ctx = HeadlessCtx::new(9);
+    // two minutes of walking in circles and swinging the axe
+    common::run(&mut ctx, 60 * 60 * 2);
+    assert!(ctx.world.player().is_some());
+}
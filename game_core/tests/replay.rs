@@ -0,0 +1,94 @@
+mod common;
+
+use game_core::{HeadlessCtx, Recording, ReplayMismatch, World};
+
+/// Runs a new world for `ticks` ticks while recording the wandering input
+fn record(seed: u64, ticks: usize) -> Recording {
+    record_with(World::new(seed), ticks)
+}
+
+/// Runs the given world for `ticks` ticks while recording the wandering input
+fn record_with(world: World, ticks: usize) -> Recording {
+    let seed = world.seed;
+    let mut ctx = HeadlessCtx::from_world(world);
+    let mut recording = Recording::new(seed);
+    for i in 0..ticks {
+        let mut input = common::wander(&ctx, i);
+        input.sprint = i % 200 < 50;
+        input.dodge = i.is_multiple_of(300);
+        input.drop_slot = (i % 400 == 399).then_some(0);
+        input.craft = (i % 500 == 250).then(|| "campfire".to_string());
+        input.switch_weapon = i % 350 == 100;
+        recording.record(1.0 / 60.0, &input);
+        ctx.step(1.0 / 60.0, input);
+    }
+    recording.finish(&ctx.world);
+    recording
+}
+
+#[test]
+fn written_recording_is_read_back_and_verifies() {
+    let recording = record(5, 600);
+    let mut bytes = Vec::new();
+    recording.write(&mut bytes).unwrap();
+
+    let read = Recording::read(&mut bytes.as_slice()).unwrap();
+    assert_eq!(read.seed, recording.seed);
+    assert_eq!(read.checksum, recording.checksum);
+    assert_eq!(read.registries, recording.registries);
+    assert!(read.ticks == recording.ticks);
+    read.verify().unwrap();
+}
+
+#[test]
+fn replay_with_other_input_does_not_verify() {
+    let mut recording = record(5, 300);
+    recording.ticks[100].move_dir = -recording.ticks[100].move_dir;
+    assert!(recording.verify().is_err());
+}
+
+/// A world whose axe hits much harder than the builtin one, like a world with weapons loaded from changed assets
+fn world_with_strong_axe(seed: u64) -> World {
+    let mut world = World::new(seed);
+    world.weapons.weapons.get_mut("axe").unwrap().damage *= 10.0;
+    world
+}
+
+#[test]
+fn replay_checks_the_registries() {
+    let recording = record_with(world_with_strong_axe(3), 300);
+    assert!(matches!(recording.verify(), Err(ReplayMismatch::Registries { .. })));
+    recording.verify_with(world_with_strong_axe(3)).unwrap();
+}
+
+#[test]
+fn read_rejects_other_files() {
+    assert!(Recording::read(&mut b"RIFF\x08\x00".as_slice()).is_err());
+
+    // a version newer than this build understands
+    let mut bytes = Vec::new();
+    Recording::new(1).write(&mut bytes).unwrap();
+    bytes[4] = 0xff;
+    assert!(Recording::read(&mut bytes.as_slice()).is_err());
+
+    // a corrupt length is not allocated up front
+    let mut bytes = Vec::new();
+    Recording::new(1).write(&mut bytes).unwrap();
+    let len = bytes.len() - 4;
+    bytes[len..].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(Recording::read(&mut bytes.as_slice()).is_err());
+
+    // truncated in the middle of the ticks
+    let mut bytes = Vec::new();
+    record(1, 10).write(&mut bytes).unwrap();
+    bytes.truncate(bytes.len() - 3);
+    assert!(Recording::read(&mut bytes.as_slice()).is_err());
+}
+
+#[test]
+fn registries_loaded_from_the_assets_match_the_builtin_ones() {
+    let mut world = World::new(1);
+    let errors = world.load_registries(concat!(env!("CARGO_MANIFEST_DIR"), "/../assets"));
+    assert!(errors.is_empty());
+    assert_eq!(world.registries_checksum(), World::new(2).registries_checksum());
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use game_core::{HeadlessCtx, SAVE_VERSION, World};
+
+#[test]
+fn save_and_load_keeps_the_world() {
+    let mut ctx = HeadlessCtx::new(3);
+    common::run(&mut ctx, 300);
+
+    let save = ctx.world.to_save_string().unwrap();
+    let loaded = World::from_save_str(&save).unwrap();
+    assert_eq!(loaded.checksum(), ctx.world.checksum());
+    assert_eq!(loaded.to_save_string().unwrap(), save);
+}
+
+#[test]
+fn loaded_world_continues_like_the_original() {
+    let mut ctx = HeadlessCtx::new(5);
+    common::run(&mut ctx, 200);
+
+    let path = std::env::temp_dir().join(format!("bfs-save-test-{}.ron", std::process::id()));
+    ctx.world.save(&path).unwrap();
+    let mut loaded = HeadlessCtx {
+        world: World::load(&path).unwrap(),
+        frame_log: Vec::new(),
+    };
+    std::fs::remove_file(&path).unwrap();
+
+    common::run(&mut ctx, 200);
+    common::run(&mut loaded, 200);
+    assert_eq!(loaded.world.checksum(), ctx.world.checksum());
+}
+
+#[test]
+fn rejects_saves_of_newer_versions() {
+    let save = World::new(1).to_save_string().unwrap();
+    let newer = save.replacen(&format!("version:{SAVE_VERSION}"), &format!("version:{}", SAVE_VERSION + 1), 1);
+    assert!(World::from_save_str(&newer).is_err());
+}
+
@@ -0,0 +1,34 @@
+mod common;
+
+use game_core::HeadlessCtx;
+use glam::Vec3;
+
+fn health(ctx: &HeadlessCtx, id: slotmap::DefaultKey) -> f32 {
+    ctx.world.entity(id).and_then(|e| e.health.as_ref()).map(|health| health.current).unwrap_or_default()
+}
+
+fn swing(ctx: &mut HeadlessCtx) {
+    let input = game_core::PlayerInputEvent {
+        use_ability: true,
+        ..common::idle(ctx)
+    };
+    ctx.step(1.0 / 60.0, input);
+    for _ in 0..60 {
+        let input = common::idle(ctx);
+        ctx.step(1.0 / 60.0, input);
+    }
+}
+
+#[test]
+fn swing_hits_the_closest_damageable_entity() {
+    let mut ctx = HeadlessCtx::new(1);
+    common::clear_around_player(&mut ctx);
+    // the campfire cannot be damaged and is closer than the crate
+    common::spawn_near_player(&mut ctx, Vec3::new(0.5, 0.0, 0.0), "campfire");
+    let near_crate = common::spawn_near_player(&mut ctx, Vec3::new(0.9, 0.0, 0.0), "loot_crate");
+    let far_crate = common::spawn_near_player(&mut ctx, Vec3::new(1.7, 0.0, 0.0), "loot_crate");
+
+    swing(&mut ctx);
+    assert!(health(&ctx, near_crate) < 20.0);
+    assert_eq!(health(&ctx, far_crate), 20.0);
+}
@@ -13,6 +13,8 @@ use ggsdk::{
 use glam::{Vec2, Vec3, Vec4};
 use glox::{Camera, FirstPersonCamera, Glox};
 
+const SAVE_PATH: &str = "savegame.ron";
+
 #[derive(Default)]
 struct App {
     pub glox: Glox,
@@ -21,6 +23,12 @@ struct App {
     pub command_queue: VecDeque<AppCommand>,
     pub flash_color: Option<Vec4>,
     pub text_commands: Vec<(Vec3, String, Vec4)>,
+    /// Recording of the current run, `None` when the run was loaded from a save
+    pub recording: Option<Recording>,
+    /// Index of the recipe selected for crafting, in the order of the recipe registry
+    pub selected_recipe: usize,
+    /// Whether the number keys place buildings instead of dropping items
+    pub build_mode: bool,
 }
 
 enum AppCommand {
@@ -48,16 +56,44 @@ enum AppCommand {
 fn texture_to_string(texture: Texture, frame: Frame) -> &'static str {
     match texture {
         Texture::None => Default::default(),
-        Texture::Tree1 => "tree",
+        Texture::Tree1 => match frame {
+            Frame::Falling => "tree_falling",
+            Frame::Dead => "stump",
+            _ => "tree",
+        },
         Texture::Zombie1 => match frame {
             Frame::Default => "zombie_0",
             Frame::Walk1 => "zombie_1",
             Frame::Walk2 => "zombie_2",
             Frame::ReadyAttack => "zombie_3",
             Frame::Attack => "zombie_4",
-            Frame::Dead => "zombie_5",
+            Frame::Falling | Frame::Dead => "zombie_5",
         },
         Texture::Grass => "grass",
+        Texture::SwampGrass => "swamp_grass",
+        Texture::Ash => "ash",
+        Texture::MeadowGrass => "meadow_grass",
+        Texture::Water => "water",
+        Texture::Dirt => "dirt",
+        Texture::Mud => "mud",
+        Texture::Rock => "rock",
+        Texture::Snow => "snow",
+        Texture::Planks => "planks",
+        Texture::Wall => "wall",
+        Texture::Crate => "crate",
+        Texture::Campfire => "campfire",
+        Texture::Wood => "wood",
+        Texture::Stone => "stone",
+        Texture::Berries => "berries",
+        Texture::Bandage => "bandage",
+        Texture::Cloth => "cloth",
+        Texture::Torch => "torch_item",
+        Texture::Spear => "spear",
+        Texture::Barricade => "barricade",
+        Texture::Knife => "knife",
+        Texture::WoodsmanAxe => "woodsman_axe",
+        Texture::Bow => "bow",
+        Texture::Arrow => "arrow",
     }
 }
 
@@ -66,10 +102,6 @@ impl Ctx for App {
         &mut self.world
     }
 
-    fn rand_u32(&mut self) -> u32 {
-        rand::random::<u32>()
-    }
-
     fn draw_tile(&mut self, origin: Vec3, texture: Texture, frame: Frame, color: Vec4) {
         self.command_queue.push_back(AppCommand::DrawTile {
             origin,
@@ -108,6 +140,40 @@ impl Ctx for App {
     }
 }
 
+impl App {
+    /// Draws the building of the first placeable item in the inventory onto the tile in front of the player
+    /// Tinted green if it can be placed there and red otherwise
+    fn draw_build_preview(&mut self) {
+        let world = &self.world;
+        let Some(inventory) = world.player().and_then(|player| player.inventory.as_ref()) else {
+            return;
+        };
+        let Some(prefab) = inventory
+            .slots
+            .iter()
+            .flatten()
+            .find_map(|stack| world.items.get(&stack.item)?.places.as_ref())
+            .and_then(|id| world.prefabs.get(id))
+        else {
+            return;
+        };
+        let Some(sprite) = &prefab.sprite else {
+            return;
+        };
+        let Some(tile_index) = world.build_target(world.player) else {
+            return;
+        };
+        let color = if world.can_build_at(tile_index) {
+            Vec4::new(0.4, 1.0, 0.4, 1.0)
+        } else {
+            Vec4::new(1.0, 0.3, 0.3, 1.0)
+        };
+        let origin = Vec3::new(tile_index.x as f32 + 0.5, tile_index.y as f32 + 0.5, 0.0);
+        let (texture, size) = (sprite.texture, sprite.size);
+        self.draw_sprite(origin, texture, Frame::Default, color, size);
+    }
+}
+
 impl ggsdk::GGApp for App {
     fn init(&mut self, g: ggsdk::InitContext) {
         self.glox.init(g.gl);
@@ -134,9 +200,16 @@ impl ggsdk::GGApp for App {
             }
         }
 
+        // registries are loaded from disk, such that they can be changed without recompiling
+        for (registry, err) in self.world.load_registries("assets") {
+            println!("failed to load {registry}, using builtin {registry}: {err}");
+        }
+
         self.world
             .events
             .push_front(Event::Restart(RestartEvent {}));
+        systems::process(self);
+        self.recording = Some(Recording::new(self.world.seed));
     }
 
     fn update(&mut self, g: ggsdk::UpdateContext) {
@@ -147,7 +220,7 @@ impl ggsdk::GGApp for App {
                 ggsdk::egui::CursorGrab::Confined,
             ));
 
-        render::render_ui(&self.world, &g);
+        render::render_ui(&self.world, self.selected_recipe, self.build_mode, &g);
 
         // Render text commands that were extracted in paint_glow
         let camera: &dyn Camera = &self.fps_camera;
@@ -198,6 +271,16 @@ impl ggsdk::GGApp for App {
         let mut move_dir = Vec2::new(0.0, 0.0);
         let mut pointer_delta = Vec2::new(0.0, 0.0);
         let mut use_ability = false;
+        let mut slot_pressed = None;
+        let mut consume = false;
+        let mut sprint = false;
+        let mut dodge = false;
+        let mut switch_weapon = false;
+        let mut craft = false;
+        let mut refuel = false;
+        let mut save_replay = false;
+        let mut save_game = false;
+        let mut load_game = false;
         g.egui_ctx.input(|x| {
             let r = x.content_rect();
             self.fps_camera.viewport_size = Vec2::new(r.width(), r.height());
@@ -218,6 +301,17 @@ impl ggsdk::GGApp for App {
                 use_ability = true;
             }
 
+            // shift sprints and V dodges in the movement direction, both use up stamina
+            sprint = x.modifiers.shift;
+            if x.key_pressed(Key::V) {
+                dodge = true;
+            }
+
+            // tab switches to the next carried weapon
+            if x.key_pressed(Key::Tab) {
+                switch_weapon = true;
+            }
+
             let delta = x.pointer.motion().unwrap_or_default();
             pointer_delta = Vec2::new(delta.x, delta.y);
 
@@ -232,6 +326,42 @@ impl ggsdk::GGApp for App {
             if x.pointer.primary_down() {
                 use_ability = true;
             }
+
+            // number keys drop the items of the matching inventory slot, or build with them in build mode
+            // with ctrl held they use up the item instead, such as eating berries
+            let slot_keys = [Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7, Key::Num8];
+            for (slot, key) in slot_keys.into_iter().enumerate() {
+                if x.key_pressed(key) {
+                    slot_pressed = Some(slot);
+                }
+            }
+            consume = x.modifiers.ctrl;
+            if x.key_pressed(Key::B) {
+                self.build_mode = !self.build_mode;
+            }
+
+            // R selects the next recipe, C crafts the selected recipe
+            if x.key_pressed(Key::R) {
+                self.selected_recipe += 1;
+            }
+            if x.key_pressed(Key::C) {
+                craft = true;
+            }
+
+            // F burns wood or a new torch to keep the torch lit
+            if x.key_pressed(Key::F) {
+                refuel = true;
+            }
+
+            if x.key_pressed(Key::F9) {
+                save_replay = true;
+            }
+            if x.key_pressed(Key::F5) {
+                save_game = true;
+            }
+            if x.key_pressed(Key::F8) {
+                load_game = true;
+            }
         });
 
         let current_camera_pos = self.fps_camera.eye;
@@ -240,14 +370,27 @@ impl ggsdk::GGApp for App {
         self.fps_camera.change_yaw(-pointer_delta.x / 100.0);
         let facing = self.fps_camera.yaw();
         let move_dir = new_camera_pos - current_camera_pos;
-        self.world
-            .events
-            .push_back(Event::PlayerInput(PlayerInputEvent {
-                player_id: self.world.player,
-                move_dir: move_dir.normalize_or_zero(),
-                facing,
-                use_ability,
-            }));
+        let recipe_count = self.world.recipes.recipes.len().max(1);
+        self.selected_recipe %= recipe_count;
+        let selected_recipe = self.world.recipes.recipes.keys().nth(self.selected_recipe).cloned();
+        let input = PlayerInputEvent {
+            player_id: self.world.player,
+            move_dir: move_dir.normalize_or_zero(),
+            facing,
+            use_ability,
+            drop_slot: slot_pressed.filter(|_| !self.build_mode && !consume),
+            craft: selected_recipe.filter(|_| craft),
+            build_slot: slot_pressed.filter(|_| self.build_mode && !consume),
+            refuel,
+            consume_slot: slot_pressed.filter(|_| consume),
+            sprint,
+            dodge,
+            switch_weapon,
+        };
+        if let Some(recording) = &mut self.recording {
+            recording.record(g.dt, &input);
+        }
+        self.world.events.push_back(Event::PlayerInput(input));
         self.world
             .events
             .push_back(Event::Tick(TickEvent { dt: g.dt }));
@@ -256,11 +399,52 @@ impl ggsdk::GGApp for App {
             .events
             .push_back(Event::PostTick(TickEvent { dt: g.dt }));
         systems::process(self);
+        if self.build_mode {
+            self.draw_build_preview();
+        }
+
+        if save_replay {
+            if let Some(recording) = &mut self.recording {
+                recording.finish(&self.world);
+                let path = format!("replay-{}.bfsr", self.world.seed);
+                match recording.save(&path) {
+                    Ok(()) => println!("replay saved to {path}"),
+                    Err(err) => println!("failed to save replay: {err}"),
+                }
+            } else {
+                println!("replays are not available for loaded games");
+            }
+        }
+
+        if save_game {
+            match self.world.save(SAVE_PATH) {
+                Ok(()) => println!("game saved to {SAVE_PATH}"),
+                Err(err) => println!("failed to save game: {err}"),
+            }
+        }
+
+        if load_game {
+            match World::load(SAVE_PATH) {
+                Ok(mut world) => {
+                    world.prefabs = std::mem::take(&mut self.world.prefabs);
+                    world.behaviours = std::mem::take(&mut self.world.behaviours);
+                    world.structures = std::mem::take(&mut self.world.structures);
+                    world.items = std::mem::take(&mut self.world.items);
+                    world.recipes = std::mem::take(&mut self.world.recipes);
+                    world.weapons = std::mem::take(&mut self.world.weapons);
+                    self.world = world;
+                    // a replay can only be recorded from the start of a run
+                    self.recording = None;
+                    println!("game loaded from {SAVE_PATH}");
+                }
+                Err(err) => println!("failed to load game: {err}"),
+            }
+        }
 
         // Synchronize camera with player position after systems have processed
         if let Some(player) = self.world.entities.get(self.world.player) {
-            self.fps_camera.eye = player.pos + Vec3::new(0.0, 0.0, 0.5);
-            self.fps_camera.yaw = player.facing;
+            self.fps_camera.eye = player.transform.pos + Vec3::new(0.0, 0.0, 0.5);
+            self.fps_camera.yaw = player.transform.facing;
         }
     }
 
@@ -268,7 +452,7 @@ impl ggsdk::GGApp for App {
         let Some(player) = self.world.entities.get(self.world.player) else {
             return;
         };
-        let player_pos = player.pos;
+        let player_pos = player.transform.pos;
         let camera_dir = self.fps_camera.direction();
         let gl = g.painter.gl();
 
@@ -360,7 +544,16 @@ impl ggsdk::GGApp for App {
 }
 
 fn main() {
-    let app = App::default();
+    // the seed can be passed as the first argument to reproduce a run
+    let seed = std::env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse::<u64>().ok())
+        .unwrap_or_else(rand::random::<u64>);
+    println!("seed: {seed}");
+    let app = App {
+        world: World::new(seed),
+        ..Default::default()
+    };
     ggsdk::GGEngine::run(
         app,
         GGRunOptions {
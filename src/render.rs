@@ -23,7 +23,7 @@ impl Render {
 }
 
 /// Renders the 2D UI elements (torch and axe)
-pub fn render_ui(world:&World, g: &ggsdk::UpdateContext) {
+pub fn render_ui(world:&World, selected_recipe: usize, build_mode: bool, g: &ggsdk::UpdateContext) {
     let painter = g.egui_ctx.layer_painter(LayerId::background());
 
     let Some(torch) = g.assets.get::<GGAtlas>("torch") else { return; };
@@ -33,22 +33,26 @@ pub fn render_ui(world:&World, g: &ggsdk::UpdateContext) {
     let w = h / 2.0;
 
     let Some(player) = world.entities.get(world.player) else { return; };
+    let move_sinus = player.mover.as_ref().map(|mover| mover.move_sinus).unwrap_or_default();
+    let fuel = player.light_source.as_ref().map(|light_source| light_source.fuel_fraction());
     {
         let f = h / 20.0;
-        let x = 0.0;//-player.move_sinus * f;
-        let y = (player.move_sinus + 1.0) * f;
-        painter.atlas(&torch, 0, Rect::from_min_max(Pos2::new(x, y), Pos2::new(w + x, screen_size.y + y)), Color32::WHITE);
+        let x = 0.0;//-move_sinus * f;
+        let y = (move_sinus + 1.0) * f;
+        // the torch darkens as it burns down
+        let c = (64.0 + 191.0 * fuel.unwrap_or(1.0).sqrt()) as u8;
+        painter.atlas(&torch, 0, Rect::from_min_max(Pos2::new(x, y), Pos2::new(w + x, screen_size.y + y)), Color32::from_rgb(c, c, c));
     }
 
     let Some(axe) = g.assets.get::<GGAtlas>("axe") else { return; };
 
   
-    let cooldown = player.ability_delta();
+    let cooldown = player.ability.as_ref().map(|ability| ability.delta()).unwrap_or(1.0);
     if cooldown == 1.0 {
         // not on cooldown, draw normally
         let f = h / 20.0;
-        let x = player.move_sinus * f;
-        let y = h / 4.0 + player.move_sinus * f;
+        let x = move_sinus * f;
+        let y = h / 4.0 + move_sinus * f;
         painter.atlas(&axe, 0, Rect::from_min_max(Pos2::new(screen_size.x - w + x, y), Pos2::new(screen_size.x + x, screen_size.y + y)), Color32::WHITE);
     } else {
         let y = cooldown * 2.0 * h;
@@ -57,6 +61,7 @@ pub fn render_ui(world:&World, g: &ggsdk::UpdateContext) {
     }
 
     // player health
+    let Some(health) = &player.health else { return; };
     use ggsdk::egui::{FontId, Align2};
     let font_id = FontId::proportional(48.0);
     
@@ -67,10 +72,10 @@ pub fn render_ui(world:&World, g: &ggsdk::UpdateContext) {
         font_id.clone(),
         Color32::WHITE,
     );
-    let health_value = format!("{:.0}", player.health.current);
-    let text_color = if player.health.current / player.health.max > 0.5 {
+    let health_value = format!("{:.0}", health.current);
+    let text_color = if health.current / health.max > 0.5 {
         Color32::from_rgb(100, 255, 100)
-    } else if player.health.current / player.health.max > 0.25 {
+    } else if health.current / health.max > 0.25 {
         Color32::from_rgb(255, 255, 100)
     } else {
         Color32::from_rgb(255, 100, 100)
@@ -82,7 +87,130 @@ pub fn render_ui(world:&World, g: &ggsdk::UpdateContext) {
         font_id,
         text_color,
     );
-   
+
+    // wielded weapon in the bottom right, with the ammo left for ranged weapons
+    if let Some(weapon) = world.weapon(world.player) {
+        let ammo = weapon.ammo.as_ref().map(|ammo| player.inventory.as_ref().map(|inventory| inventory.count(ammo)).unwrap_or_default());
+        let name = match ammo {
+            Some(count) => format!("{} ({})", weapon.name, count),
+            None => weapon.name.clone(),
+        };
+        painter.text(
+            Pos2::new(screen_size.x - 30.0, screen_size.y - 90.0),
+            Align2::RIGHT_BOTTOM,
+            format!("{}   [Tab] switch", name),
+            FontId::proportional(24.0),
+            Color32::WHITE,
+        );
+    }
+
+    // survival stats as bars above the health, red once they run low
+    if let Some(survival) = &player.survival {
+        let stats = [
+            ("Food", survival.food),
+            ("Water", survival.water),
+            ("Warmth", survival.warmth),
+            ("Stamina", survival.stamina),
+        ];
+        let font_id = FontId::proportional(20.0);
+        let bar_size = Vec2::new(160.0, 14.0);
+        for (i, (name, value)) in stats.into_iter().enumerate() {
+            let y = screen_size.y - 130.0 - 24.0 * (stats.len() - 1 - i) as f32;
+            let fraction = (value / survival.max).clamp(0.0, 1.0);
+            let color = if fraction > 0.25 {
+                Color32::from_rgb(100, 200, 255)
+            } else {
+                Color32::from_rgb(255, 100, 100)
+            };
+            painter.text(Pos2::new(30.0, y), Align2::LEFT_CENTER, name, font_id.clone(), Color32::WHITE);
+            let rect = Rect::from_min_size(Pos2::new(120.0, y - bar_size.y / 2.0), ggsdk::egui::vec2(bar_size.x, bar_size.y));
+            painter.rect_filled(rect, 2.0, Color32::from_black_alpha(160));
+            let filled = Rect::from_min_size(rect.min, ggsdk::egui::vec2(bar_size.x * fraction, bar_size.y));
+            painter.rect_filled(filled, 2.0, color);
+        }
+    }
+
+    // torch fuel, warning when it is about to run out
+    if let Some(fuel) = fuel {
+        let color = if fuel > 0.25 {
+            Color32::from_rgb(255, 200, 100)
+        } else {
+            Color32::from_rgb(255, 100, 100)
+        };
+        let text = if fuel > 0.0 {
+            format!("Torch: {:.0}%   [F] refuel", fuel * 100.0)
+        } else {
+            "Torch burnt out!   [F] refuel".to_string()
+        };
+        painter.text(Pos2::new(30.0, screen_size.y - 90.0), Align2::LEFT_BOTTOM, text, FontId::proportional(24.0), color);
+    }
+
+    // day and time of day, and the win once enough nights were survived
+    let clock_text = format!("Day {} of {} - {:?}", world.clock.day().min(game_core::NIGHTS_TO_SURVIVE), game_core::NIGHTS_TO_SURVIVE, world.clock.phase());
+    painter.text(Pos2::new(30.0, 30.0), Align2::LEFT_TOP, clock_text, FontId::proportional(32.0), Color32::WHITE);
+    if world.clock.has_won() {
+        painter.text(
+            Pos2::new(screen_size.x / 2.0, screen_size.y / 3.0),
+            Align2::CENTER_CENTER,
+            format!("You survived {} nights!", game_core::NIGHTS_TO_SURVIVE),
+            FontId::proportional(64.0),
+            Color32::from_rgb(255, 220, 100),
+        );
+    }
+
+    // inventory slots along the bottom, numbered by the key dropping them or building with them
+    let Some(inventory) = &player.inventory else { return; };
+    let font_id = FontId::proportional(20.0);
+    let slot_size = Vec2::new(110.0, 40.0);
+    let left = (screen_size.x - slot_size.x * inventory.slots.len() as f32) / 2.0;
+    for (i, slot) in inventory.slots.iter().enumerate() {
+        let min = Pos2::new(left + slot_size.x * i as f32, screen_size.y - slot_size.y - 10.0);
+        let rect = Rect::from_min_size(min, ggsdk::egui::vec2(slot_size.x - 4.0, slot_size.y));
+        painter.rect_filled(rect, 4.0, Color32::from_black_alpha(160));
+        let text = match slot {
+            Some(stack) => {
+                let label = world.items.get(&stack.item).map(|def| def.label(stack.count)).unwrap_or_else(|| stack.item.clone());
+                format!("{} {}", i + 1, label)
+            }
+            None => format!("{}", i + 1),
+        };
+        painter.text(rect.left_center() + ggsdk::egui::vec2(6.0, 0.0), Align2::LEFT_CENTER, text, font_id.clone(), Color32::WHITE);
+    }
+
+    // selected recipe above the inventory, or the progress of the recipe being crafted
+    let item_name = |item: &str| world.items.get(item).map(|def| def.name.clone()).unwrap_or_else(|| item.to_string());
+    let recipe_text = if let Some(crafting) = &player.crafting {
+        let recipe = world.recipes.get(&crafting.recipe);
+        let total = recipe.map(|recipe| recipe.craft_sec).unwrap_or(1.0).max(0.01);
+        let name = recipe.map(|recipe| item_name(&recipe.output.item)).unwrap_or_default();
+        let progress = (1.0 - crafting.timer_sec / total).clamp(0.0, 1.0);
+        Some((format!("Crafting {}... {:.0}%", name, progress * 100.0), Color32::WHITE))
+    } else if let Some((_, recipe)) = world.recipes.recipes.iter().nth(selected_recipe) {
+        let inputs: Vec<String> = recipe.inputs.iter().map(|input| format!("{} {}", input.count, item_name(&input.item))).collect();
+        let mut text = format!("[C] craft {} from {}", item_name(&recipe.output.item), inputs.join(", "));
+        if let Some(station) = recipe.station {
+            text += &format!(" near {station:?}");
+        }
+        text += "   [R] next recipe";
+        let can_craft = recipe.has_inputs(inventory);
+        Some((text, if can_craft { Color32::WHITE } else { Color32::GRAY }))
+    } else {
+        None
+    };
+    let recipe_text = if build_mode {
+        Some(("BUILD MODE: [1-8] place building   [B] leave".to_string(), Color32::from_rgb(100, 255, 100)))
+    } else {
+        recipe_text
+    };
+    if let Some((text, color)) = recipe_text {
+        painter.text(
+            Pos2::new(screen_size.x / 2.0, screen_size.y - slot_size.y - 20.0),
+            Align2::CENTER_BOTTOM,
+            text,
+            font_id,
+            color,
+        );
+    }
 }
 
 /// Renders the 3D world using OpenGL
@@ -95,7 +223,7 @@ pub fn render_3d_world(
     let Some(player) = world.entities.get(world.player) else {
         return;
     };
-    let player_pos = player.pos;
+    let player_pos = player.transform.pos;
     let player_tile_pos = player.tile_index();
     let camera: &dyn Camera = fps_camera;
     let Some(texture) = g.assets.get::<GGAtlas>("grass") else {
@@ -145,17 +273,20 @@ pub fn render_3d_world(
 
     // draw some sprites / billboards
     for entity in world.entities.values() {
+        let Some(sprite) = &entity.sprite else {
+            continue;
+        };
         let mut draw = glox.draw_builder(gl, camera);
         let texture = match entity.variant {
             EntityVariant::Player => "grass",
             _ => "tree"
         };
-        let scaling_factor = entity.sprite_size;
+        let scaling_factor = sprite.size;
         if let Some(atlas) = g.assets.get::<GGAtlas>(texture) {
             let texture = g.painter.texture(atlas.texture_id()).unwrap();
             draw.bind_texture(texture.into());
         }
-        let p = entity.pos;
+        let p = entity.transform.pos;
         let d = p - player_pos;
         let d = d.length();
         let d = light(d);